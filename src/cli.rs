@@ -1,7 +1,4 @@
-use clap::{
-    command,
-    Parser,
-};
+use clap::Parser;
 use std::path::Path;
 
 #[derive(Debug, Clone)]
@@ -19,6 +16,11 @@ pub enum SwitchFlag {
     OmitContract,
     OmitReadStorage,
     GroupFloatingStorage,
+    Deployment,
+    LinkImplementations,
+    Report,
+    Actors,
+    ValueFlow,
 }
 
 /// Sol2Ink - tool to convert Solidity smart contracts to Ink! smart contracts
@@ -52,6 +54,16 @@ fn sol_file_parser(s: &str) -> Result<CliInput, String> {
         Ok(CliInput::SwitchFlag(SwitchFlag::OmitContract))
     } else if result == *"omit_read_storage" {
         Ok(CliInput::SwitchFlag(SwitchFlag::OmitReadStorage))
+    } else if result == *"deployment" {
+        Ok(CliInput::SwitchFlag(SwitchFlag::Deployment))
+    } else if result == *"link_implementations" {
+        Ok(CliInput::SwitchFlag(SwitchFlag::LinkImplementations))
+    } else if result == *"report" {
+        Ok(CliInput::SwitchFlag(SwitchFlag::Report))
+    } else if result == *"actors" {
+        Ok(CliInput::SwitchFlag(SwitchFlag::Actors))
+    } else if result == *"value_flow" {
+        Ok(CliInput::SwitchFlag(SwitchFlag::ValueFlow))
     } else {
         Ok(CliInput::SpecificContract(result))
     }
@@ -8,7 +8,10 @@ use std::{
         prelude::*,
         BufReader,
     },
-    path::Path,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 
 /// Reads the file to be transpiled and returns its content as a String
@@ -46,16 +49,272 @@ pub fn get_solidity_files_from_directory(dir: &str) -> std::io::Result<Vec<Strin
     Ok(paths)
 }
 
+/// Returns the canonical path of a file, so the same file is not loaded twice
+///
+/// `path` the path to the file
+pub fn normalize_path(path: &str) -> std::io::Result<String> {
+    Ok(fs::canonicalize(path)?.to_string_lossy().to_string())
+}
+
+/// Returns the root directory of the project a file belongs to
+///
+/// The root is the closest directory containing a Foundry or Hardhat configuration,
+/// `remappings.txt` or `package.json`, the directory of the file otherwise
+///
+/// `file` the path to the file
+pub fn find_project_root(file: &str) -> PathBuf {
+    let directory = Path::new(file).parent().unwrap_or(Path::new("."));
+
+    directory
+        .ancestors()
+        .find(|ancestor| {
+            [
+                "foundry.toml",
+                "remappings.txt",
+                "hardhat.config.js",
+                "hardhat.config.ts",
+                "package.json",
+            ]
+            .iter()
+            .any(|config| ancestor.join(config).exists())
+        })
+        .unwrap_or(directory)
+        .to_path_buf()
+}
+
+/// Reads the import remappings of a project from `remappings.txt` and `foundry.toml`
+///
+/// `root` the root directory of the project
+///
+/// Returns the remapped prefixes with their targets, the longest prefixes first
+pub fn read_remappings(root: &Path) -> Vec<(String, String)> {
+    let mut remappings = Vec::default();
+
+    if let Ok(content) = read_file(&root.join("remappings.txt").to_string_lossy().to_string()) {
+        remappings.extend(content.lines().map(str::to_string));
+    }
+
+    if let Ok(content) = read_file(&root.join("foundry.toml").to_string_lossy().to_string()) {
+        remappings.extend(foundry_remappings(&content));
+    }
+
+    let mut remappings = remappings
+        .iter()
+        .filter_map(|remapping| remapping.trim().split_once('='))
+        .map(|(prefix, target)| {
+            // the context of a remapping (`context:prefix=target`) is ignored
+            let prefix = prefix.split_once(':').map_or(prefix, |(_, prefix)| prefix);
+            let target = root.join(target.trim());
+
+            (
+                prefix.trim().to_string(),
+                target.to_string_lossy().to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    remappings.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+    remappings
+}
+
+/// Returns the remappings of the profiles of a `foundry.toml`, e.g.
+/// `remappings = ["prefix/=target/"]` of `[profile.default]`
+///
+/// Other keys and tables are ignored, as well as a file which is not valid TOML
+///
+/// `content` the content of the `foundry.toml`
+fn foundry_remappings(content: &str) -> Vec<String> {
+    let Ok(config) = content.parse::<toml::Table>() else {
+        return Vec::default()
+    };
+    let Some(profiles) = config
+        .get("profile")
+        .and_then(|profiles| profiles.as_table())
+    else {
+        return Vec::default()
+    };
+
+    profiles
+        .values()
+        .filter_map(|profile| profile.get("remappings"))
+        .filter_map(|remappings| remappings.as_array())
+        .flatten()
+        .filter_map(|remapping| remapping.as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Resolves the path of an imported file
+///
+/// Relative imports are resolved against the importing file, the others are remapped,
+/// then looked up in the project root and in the `node_modules` directories
+///
+/// `file` the path to the importing file
+/// `import` the imported path as written in the import directive
+/// `root` the root directory of the project
+/// `remappings` the remappings of the project
+///
+/// Returns the canonical path of the imported file if it exists
+pub fn resolve_import(
+    file: &str,
+    import: &str,
+    root: &Path,
+    remappings: &[(String, String)],
+) -> Option<String> {
+    let directory = Path::new(file).parent().unwrap_or(Path::new("."));
+
+    let mut candidates = Vec::default();
+
+    if import.starts_with("./") || import.starts_with("../") {
+        candidates.push(directory.join(import));
+    } else {
+        if let Some((prefix, target)) = remappings
+            .iter()
+            .find(|(prefix, _)| import.starts_with(prefix.as_str()))
+        {
+            candidates.push(PathBuf::from(format!(
+                "{target}{}",
+                &import[prefix.len()..]
+            )));
+        }
+
+        candidates.push(root.join(import));
+        candidates.extend(
+            directory
+                .ancestors()
+                .map(|ancestor| ancestor.join("node_modules").join(import)),
+        );
+    }
+
+    candidates
+        .iter()
+        .find(|candidate| candidate.is_file())
+        .and_then(|candidate| normalize_path(&candidate.to_string_lossy()).ok())
+}
+
 /// writes the output trait to a file
 ///
-/// `tokens` the transpiled file in the form of TokenStream
-/// `file_home` the home directory of the file we are parsing, or the directory we are parsing
-/// `trait_name` the name of the trait we are writing
-pub fn write_mermaid(mermaid_string: &String) -> std::io::Result<()> {
+/// `mermaid_string` the generated mermaid diagram
+/// `file_name` the name of the file in the output directory
+pub fn write_mermaid(mermaid_string: &String, file_name: &str) -> std::io::Result<()> {
     fs::create_dir_all("./output")?;
 
-    let mut mermaid = File::create("./output/output.txt")?;
+    let mut mermaid = File::create(format!("./output/{file_name}"))?;
     mermaid.write_all(mermaid_string.as_bytes())?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    /// Directory holding the files of a single test, removed when the test ends
+    struct Project(PathBuf);
+
+    impl Project {
+        fn new(files: &[&str]) -> Self {
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let root = std::env::temp_dir().join(format!(
+                "sol2mermaid-imports-{}-{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+
+            for file in files {
+                let file = root.join(file);
+                fs::create_dir_all(file.parent().unwrap()).unwrap();
+                fs::write(file, "").unwrap();
+            }
+
+            Self(root)
+        }
+
+        /// Returns the canonical path of a file of the project
+        fn path(&self, file: &str) -> String {
+            normalize_path(&self.0.join(file).to_string_lossy()).unwrap()
+        }
+    }
+
+    impl Drop for Project {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn imports_are_resolved_relatively_remapped_and_in_node_modules() {
+        let project = Project::new(&[
+            "src/Main.sol",
+            "src/utils/Math.sol",
+            "deps/short/token/ERC20.sol",
+            "deps/long/ERC20.sol",
+            "node_modules/@oz/access/Ownable.sol",
+        ]);
+        fs::write(
+            project.0.join("remappings.txt"),
+            "lib/=deps/short/\nlib/token/=deps/long/\n",
+        )
+        .unwrap();
+
+        let main = project.path("src/Main.sol");
+        let root = find_project_root(&main);
+        let remappings = read_remappings(&root);
+        let resolve = |import: &str| resolve_import(&main, import, &root, &remappings);
+
+        assert_eq!(
+            normalize_path(&root.to_string_lossy()).unwrap(),
+            project.path("")
+        );
+        assert_eq!(
+            resolve("./utils/Math.sol"),
+            Some(project.path("src/utils/Math.sol"))
+        );
+        assert_eq!(
+            resolve("../src/utils/Math.sol"),
+            Some(project.path("src/utils/Math.sol"))
+        );
+        // the longest prefix wins
+        assert_eq!(
+            resolve("lib/token/ERC20.sol"),
+            Some(project.path("deps/long/ERC20.sol"))
+        );
+        assert_eq!(
+            resolve("@oz/access/Ownable.sol"),
+            Some(project.path("node_modules/@oz/access/Ownable.sol"))
+        );
+        assert_eq!(resolve("./Missing.sol"), None);
+    }
+
+    #[test]
+    fn foundry_remappings_are_read_from_profiles_only() {
+        let content = r#"
+[profile.default]
+auto_detect_remappings = false
+# remappings = ["commented/=nowhere/"]
+remappings = [
+    "dep/=lib/dep/src/",
+]
+
+[profile.ci]
+remappings = ["ci/=lib/ci/"]
+
+[rpc_endpoints]
+mainnet = "https://example.invalid"
+"#;
+
+        let mut remappings = foundry_remappings(content);
+        remappings.sort();
+
+        assert_eq!(remappings, vec!["ci/=lib/ci/", "dep/=lib/dep/src/"]);
+    }
+
+    #[test]
+    fn invalid_foundry_toml_has_no_remappings() {
+        assert!(foundry_remappings("remappings = [").is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::structures::{
+    Call,
+    CallType,
+    Contract,
+    Function,
+    WriteAfterInteraction,
+};
+
+/// Step of the control flow of a function, built from the flow markers of its calls
+enum Flow {
+    Call(Call),
+    Branch(Vec<Vec<Flow>>), // alternative paths, only one of them is executed
+    Loop(Vec<Flow>),        // steps which may be skipped or repeated
+    Body(Vec<Flow>),        // steps of an inlined function body, a `return` leaves them
+}
+
+/// Returns the storage writes of a function which may happen after the function interacted
+/// with another contract or account, violating checks-effects-interactions
+///
+/// Functions of the contract called by the function are followed, paths ending with a revert
+/// do not continue and paths ending with a `return` continue after the returning function body
+///
+/// `contract` the contract of the function
+/// `function` the analyzed function
+pub fn writes_after_interactions(
+    contract: &Contract,
+    function: &Function,
+) -> Vec<WriteAfterInteraction> {
+    let mut walker = Walker {
+        contract,
+        stack: vec![function.header.signature()],
+        findings: Vec::default(),
+        returns: Vec::default(),
+    };
+    walker.walk_body(&build_flow(&function.calls, &mut 0), None);
+
+    walker.findings
+}
+
+/// Builds the control flow from the calls until the end of the current sequence,
+/// i.e. the end of a path of a branch, of a loop or of an inlined function body
+///
+/// `calls` the ordered calls of a function
+/// `index` the position of the next call
+fn build_flow(calls: &[Call], index: &mut usize) -> Vec<Flow> {
+    let mut flow = Vec::default();
+
+    while let Some(call) = calls.get(*index) {
+        *index += 1;
+        match call {
+            Call::Branch => {
+                let mut paths = vec![build_flow(calls, index)];
+                while *index < calls.len() && calls[*index - 1] == Call::Path {
+                    paths.push(build_flow(calls, index));
+                }
+                flow.push(Flow::Branch(paths));
+            }
+            Call::Loop => flow.push(Flow::Loop(build_flow(calls, index))),
+            Call::Enter => flow.push(Flow::Body(build_flow(calls, index))),
+            Call::Path | Call::Merge | Call::EndLoop | Call::Leave => return flow,
+            _ => flow.push(Flow::Call(call.clone())),
+        }
+    }
+
+    flow
+}
+
+struct Walker<'a> {
+    contract: &'a Contract,
+    // functions being walked, to not loop on recursion
+    stack: Vec<String>,
+    findings: Vec<WriteAfterInteraction>,
+    // first interactions made before each `return` of the walked function bodies
+    returns: Vec<Vec<Option<Call>>>,
+}
+
+impl Walker<'_> {
+    /// Walks the flow of a function body, it ends where it falls through or returns
+    ///
+    /// `flow` the steps of the function body
+    /// `interaction` the first interaction made before the function body, if any
+    ///
+    /// Returns the first interaction made until the end of the function body, or `None` if
+    /// all of the paths revert
+    fn walk_body(&mut self, flow: &[Flow], interaction: Option<Call>) -> Option<Option<Call>> {
+        self.returns.push(Vec::default());
+        let end = self.walk(flow, interaction);
+        let returns = self.returns.pop().unwrap_or_default();
+
+        let ends = end.into_iter().chain(returns).collect::<Vec<_>>();
+        if ends.is_empty() {
+            return None
+        }
+        Some(ends.into_iter().flatten().next())
+    }
+
+    /// Walks the flow and collects the writes made after an interaction
+    ///
+    /// `flow` the walked steps
+    /// `interaction` the first interaction made before the steps, if any
+    ///
+    /// Returns the first interaction made until the end of the steps, or `None` if all of
+    /// the paths revert or return
+    fn walk(&mut self, flow: &[Flow], mut interaction: Option<Call>) -> Option<Option<Call>> {
+        for step in flow {
+            match step {
+                Flow::Call(Call::Revert(..)) => return None,
+                Flow::Call(Call::Return) => {
+                    if let Some(returns) = self.returns.last_mut() {
+                        returns.push(interaction);
+                    }
+                    return None
+                }
+                Flow::Call(call) if call.is_interaction() => {
+                    interaction = interaction.or(Some(call.clone()));
+                }
+                Flow::Call(call @ (Call::WriteStorage(..) | Call::WriteStorageField(..)))
+                    if !call.is_immutable_access() =>
+                {
+                    if let Some(interaction) = &interaction {
+                        if !self.findings.iter().any(|finding| &finding.write == call) {
+                            self.findings.push(WriteAfterInteraction {
+                                write: call.clone(),
+                                interaction: interaction.clone(),
+                            });
+                        }
+                    }
+                }
+                Flow::Call(
+                    Call::Read(CallType::CallingFunction, symbol)
+                    | Call::Write(CallType::CallingFunction, symbol),
+                ) if !self.stack.contains(&symbol.member) => {
+                    // functions of the bases were already merged into the contract
+                    let Some(function) = self
+                        .contract
+                        .functions
+                        .iter()
+                        .find(|function| function.header.signature() == symbol.member)
+                    else {
+                        continue
+                    };
+                    let flow = build_flow(&function.calls, &mut 0);
+
+                    self.stack.push(symbol.member.clone());
+                    let end = self.walk_body(&flow, interaction.clone());
+                    self.stack.pop();
+
+                    interaction = end?;
+                }
+                Flow::Call(_) => (),
+                Flow::Branch(paths) => {
+                    let ends = paths
+                        .iter()
+                        .filter_map(|path| self.walk(path, interaction.clone()))
+                        .collect::<Vec<_>>();
+                    if ends.is_empty() {
+                        return None
+                    }
+                    interaction = interaction.or(ends.into_iter().flatten().next());
+                }
+                Flow::Body(body) => interaction = self.walk_body(body, interaction)?,
+                Flow::Loop(body) => {
+                    // the next iteration runs after the interactions of the previous one
+                    if let Some(first) = self.walk(body, interaction.clone()) {
+                        let second = self.walk(body, first.clone()).flatten();
+                        interaction = interaction.or(first).or(second);
+                    }
+                }
+            }
+        }
+
+        Some(interaction)
+    }
+}
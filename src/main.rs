@@ -1,12 +1,11 @@
-#![feature(string_remove_matches)]
-#![feature(if_let_guard)]
-
 extern crate core;
 
 pub mod cli;
 pub mod file_utils;
+pub mod flow;
 pub mod parser;
 pub mod poseidon;
+pub mod report;
 pub mod structures;
 
 use cli::SwitchFlag;
@@ -15,7 +14,12 @@ use parser::Parser;
 use structures::{
     Call,
     CallType,
+    Contract,
+    Expression,
+    Function,
+    Interface,
     PoseidonOptions,
+    Symbol,
 };
 
 use crate::{
@@ -49,6 +53,11 @@ fn main() {
     let mut current_flag = SwitchFlag::None;
     let mut omit_read_storage = false;
     let mut group_floating_storage = false;
+    let mut deployment = false;
+    let mut link_implementations = false;
+    let mut report = false;
+    let mut actors = false;
+    let mut value_flow = false;
 
     for input in inputs.clone() {
         match input {
@@ -56,6 +65,11 @@ fn main() {
                 match switch_flag {
                     SwitchFlag::OmitReadStorage => omit_read_storage = true,
                     SwitchFlag::GroupFloatingStorage => group_floating_storage = true,
+                    SwitchFlag::Deployment => deployment = true,
+                    SwitchFlag::LinkImplementations => link_implementations = true,
+                    SwitchFlag::Report => report = true,
+                    SwitchFlag::Actors => actors = true,
+                    SwitchFlag::ValueFlow => value_flow = true,
                     _ => current_flag = switch_flag,
                 }
             }
@@ -75,12 +89,17 @@ fn main() {
         omitted,
         omit_read_storage,
         group_floating_storage,
+        deployment,
+        link_implementations,
+        report,
+        actors,
+        value_flow,
     };
 
     for input in inputs {
         match input {
             CliInput::SolidityFile(file) => {
-                match run(&[file.clone()], &options) {
+                match run(std::slice::from_ref(&file), &options) {
                     Ok(_) => {
                         println!("Successfully parsed {file}");
                     }
@@ -107,23 +126,110 @@ fn main() {
     }
 }
 
+/// Contracts and interfaces charted from the parsed files
+struct Analysis {
+    contracts: Vec<Contract>,
+    interfaces: Vec<Interface>,
+    slots: HashMap<Symbol, Vec<String>>, // fields of the storage slots
+    namespaces: Vec<String>,             // storage namespaces, e.g. `diamond.standard.storage`
+}
+
 /// Runs the parser on the selected contracts
 ///
 /// `home` the home directory of a single file, or the directory we are parsing
 /// `path` the paths to the files we want to parse
 fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
+    let analysis = analyze(path, options)?;
+
+    let output = poseidon::generate_mermaid(
+        &analysis.contracts,
+        &analysis.interfaces,
+        &analysis.slots,
+        &analysis.namespaces,
+        options,
+    );
+    file_utils::write_mermaid(&output, "output.txt")?;
+
+    if options.deployment {
+        let output = poseidon::generate_deployment_mermaid(&analysis.contracts);
+        file_utils::write_mermaid(&output, "deployment.txt")?;
+    }
+
+    if options.report {
+        let output = report::generate_report(&analysis.contracts);
+        file_utils::write_mermaid(&output, "report.txt")?;
+        let output = report::generate_access_table(&analysis.contracts);
+        file_utils::write_mermaid(&output, "access.md")?;
+        let output = report::generate_access_csv(&analysis.contracts);
+        file_utils::write_mermaid(&output, "access.csv")?;
+    }
+
+    Ok(())
+}
+
+/// Parses the files and merges the inheritance trees and the library calls of the selected
+/// contracts
+///
+/// `path` the paths to the files we want to parse
+fn analyze(path: &[String], options: &PoseidonOptions) -> Result<Analysis, ParserError> {
     initialize_parser!(parser);
 
+    // the requested files come first, followed by the files they import
+    let mut requested = Vec::default();
     for file in path {
+        let file = file_utils::normalize_path(file)?;
+        if !requested.contains(&file) {
+            requested.push(file);
+        }
+    }
+
+    let mut files = requested.clone();
+    // file => files it imports
+    let mut imports: HashMap<String, Vec<String>> = HashMap::new();
+    let mut remappings_map = HashMap::new();
+    let mut index = 0;
+
+    while index < files.len() {
+        let file = files[index].clone();
+        let content = file_utils::read_file(&file)?;
+        let root = file_utils::find_project_root(&file);
+        let remappings = remappings_map
+            .entry(root.clone())
+            .or_insert_with(|| file_utils::read_remappings(&root));
+
+        for import in parser.extract_imports(&content)? {
+            match file_utils::resolve_import(&file, &import, &root, remappings) {
+                Some(imported) => {
+                    imports
+                        .entry(file.clone())
+                        .or_default()
+                        .push(imported.clone());
+                    if !files.contains(&imported) {
+                        files.push(imported);
+                    }
+                }
+                None => eprintln!("warning: could not resolve import {import} in {file}"),
+            }
+        }
+
+        index += 1;
+    }
+
+    for file in files.iter() {
         let content = file_utils::read_file(file)?;
 
-        parser.extract_all_structs(&content)?;
+        parser.extract_all_structs(file, &content)?;
     }
 
-    for file in path {
+    // contracts are referred to by their names, the ones visible in a file are the imported ones
+    for file in files.iter() {
+        parser.register_imports(file, &imports);
+    }
+
+    for file in files.iter() {
         let content = file_utils::read_file(file)?;
 
-        parser.extract_storage_pointers(&content)?;
+        parser.extract_storage_pointers(file, &content)?;
     }
 
     let mut to_proccess_vec = Vec::default();
@@ -131,135 +237,117 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
     let mut outputs = HashMap::new();
     let mut processed_map = HashMap::new();
     let mut processed_vec = Vec::default();
-    let mut slots_map: HashMap<String, Vec<String>> = HashMap::new();
-
-    for file in path {
+    let mut slots_map: HashMap<Symbol, Vec<String>> = HashMap::new();
+    // contracts, interfaces and libraries are keyed by the file defining them and their name
+    // contracts as they were parsed, used to merge the inheritance tree
+    let mut contracts = HashMap::new();
+    // interfaces as they were parsed, used to merge the functions of their bases
+    let mut interfaces = HashMap::new();
+    let mut processed_interfaces = Vec::default();
+    // only contracts of the requested files are charted, imported ones are just analyzed
+    let mut charted = Vec::default();
+
+    for file in files.iter() {
         let content = file_utils::read_file(file)?;
-        let output = parser.parse_file(&content)?;
+        let output = parser.parse_file(file, &content)?;
 
         for parser_output in output {
             match parser_output.clone() {
                 ParserOutput::Contract(name, _)
                 | ParserOutput::Interface(name, _)
                 | ParserOutput::Library(name, _) => {
-                    to_proccess_map.insert(name.clone(), ());
-                    to_proccess_vec.push(name.clone());
-                    outputs.insert(name.clone(), parser_output.clone());
+                    let key = (file.clone(), name);
+                    to_proccess_map.insert(key.clone(), ());
+                    to_proccess_vec.push(key.clone());
+                    outputs.insert(key.clone(), parser_output.clone());
+
+                    if requested.contains(file) {
+                        charted.push(key);
+                    }
                 }
                 ParserOutput::None => (),
             }
+
+            match parser_output {
+                ParserOutput::Contract(name, contract) => {
+                    contracts.insert((file.clone(), name), contract);
+                }
+                ParserOutput::Interface(name, interface) => {
+                    interfaces.insert((file.clone(), name), interface);
+                }
+                _ => (),
+            }
         }
 
         parser.clear();
     }
 
     let mut index = 0;
+    // number of times we skipped processing in a row
+    let mut stalled = 0;
     while !to_proccess_vec.is_empty() {
-        let to_proccess = to_proccess_vec.get(index).unwrap();
-        let parser_output = outputs.get(to_proccess).unwrap();
+        let key = to_proccess_vec.get(index).unwrap().clone();
+        let parser_output = outputs.get(&key).unwrap();
 
-        // go through bases and check if processed
+        // libraries might use each other, in that case we stop waiting for them
+        let can_wait = stalled < to_proccess_vec.len();
 
         match parser_output {
             ParserOutput::Contract(name, contract) => {
-                let mut new_contract = contract.clone();
+                let linearization = parser.linearize(&key)?;
+                let mut new_contract = merge_contract(contract, &linearization, &contracts);
                 let mut processed = true;
 
-                for base in contract.base.clone() {
-                    // if the base needs to be processed process it
-                    if to_proccess_map.contains_key(&base) {
-                        index += 1;
-                        if index == to_proccess_vec.len() {
-                            index = 0
-                        }
-                        processed = false;
-                        break;
-                    }
-                    // else we can add its functions to the contract
-                    if let Some(ParserOutput::Contract(_, contract)) = outputs.get(&base) {
-                        new_contract.fields.append(&mut contract.fields.clone());
-
-                        for new_slot in new_contract.slots.clone() {
-                            if let Some(fields) = slots_map.get(&new_slot.name) {
-                                let mut current_fields = new_slot.fields.clone();
-                                current_fields.extend(fields.clone());
-                                current_fields.sort();
-                                current_fields.dedup();
-
-                                slots_map.insert(new_slot.name.clone(), current_fields);
-                            } else {
-                                slots_map.insert(new_slot.name.clone(), new_slot.fields);
-                            }
-                        }
-
-                        let mut new_functions = contract
-                            .functions
-                            .iter()
-                            .map(|function| {
-                                let mut new_function = function.clone();
+                for new_slot in contract.slots.clone() {
+                    if let Some(fields) = slots_map.get(&new_slot.symbol) {
+                        let mut current_fields = new_slot.fields.clone();
+                        current_fields.extend(fields.clone());
+                        current_fields.sort();
+                        current_fields.dedup();
 
-                                new_function.calls = function
-                                    .calls
-                                    .iter()
-                                    .map(|call| {
-                                        match call {
-                                            Call::Read(call_type, _, _)
-                                            | Call::ReadStorage(call_type, _, _)
-                                            | Call::WriteStorage(call_type, _, _)
-                                            | Call::Write(call_type, _, _) => {
-                                                if let CallType::CallingStoragePointer = call_type {
-                                                    call.clone()
-                                                } else {
-                                                    call.change_contract(&new_contract.name.clone())
-                                                }
-                                            }
-                                            Call::Library(_, _) => call.clone(),
-                                        }
-                                    })
-                                    .collect();
-                                new_function
-                            })
-                            .collect();
-
-                        new_contract.functions.append(&mut new_functions);
-                        new_contract
-                            .modifiers
-                            .append(&mut contract.modifiers.clone());
+                        slots_map.insert(new_slot.symbol.clone(), current_fields);
+                    } else {
+                        slots_map.insert(new_slot.symbol.clone(), new_slot.fields);
                     }
                 }
 
-                if !processed {
-                    continue
-                }
-
                 // go through Library calls of each of the functions and remap it
                 let mut new_functions = Vec::default();
 
                 for function in new_contract.functions.clone() {
                     let mut new_calls = Vec::default();
                     for call in function.calls.clone() {
-                        if let Call::Library(library_struct_name, library_function) = call.clone() {
-                            // @todo we are optimistic here
-                            let library_name =
-                                library_struct_name.split('_').next().unwrap_or_default();
-                            if processed_map.contains_key(library_name) {
+                        if let Call::Library(library_function) = call.clone() {
+                            let library = (
+                                library_function.source_unit.clone(),
+                                library_function.contract.clone(),
+                            );
+                            if to_proccess_map.contains_key(&library) && can_wait {
                                 processed = false;
                                 break
                             }
-                            if let Some(ParserOutput::Library(_, contract)) =
-                                outputs.get(library_name)
+                            if let Some(ParserOutput::Library(_, contract)) = outputs.get(&library)
                             {
                                 // find the function we are looking for
                                 let calls = contract
                                     .functions
                                     .iter()
-                                    .filter(|function| function.header.name == library_function)
+                                    .filter(|function| {
+                                        function.header.signature() == library_function.member
+                                    })
                                     .flat_map(|function| function.calls.clone())
+                                    // events emitted by the library are emitted by the contract
+                                    .map(|call| {
+                                        match call {
+                                            Call::Emit(..) => call.change_contract(&new_contract),
+                                            _ => call,
+                                        }
+                                    })
                                     .collect::<Vec<_>>();
 
                                 // calls might work some storage slots that are not saved yet
 
-                                new_calls.extend(calls);
+                                new_calls.extend(inline(calls));
                             }
                         } else {
                             new_calls.push(call);
@@ -276,33 +364,39 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
                 }
 
                 if !processed {
+                    stalled += 1;
+                    index = (index + 1) % to_proccess_vec.len();
                     continue
                 }
 
                 new_contract.functions = new_functions;
 
-                if !options.omitted.contains(&new_contract.name)
+                if charted.contains(&key)
+                    && !options.omitted.contains(&new_contract.name)
                     && (options.contracts.is_empty()
                         || options.contracts.contains(&new_contract.name))
                 {
                     processed_vec.push(new_contract.clone());
                 }
 
-                processed_map.insert(name.clone(), contract.clone());
+                processed_map.insert(key.clone(), contract.clone());
+                stalled = 0;
                 to_proccess_vec.remove(index);
-                to_proccess_map.remove(&name.clone());
+                to_proccess_map.remove(&key);
                 outputs.insert(
-                    name.clone(),
+                    key.clone(),
                     ParserOutput::Contract(name.clone(), new_contract),
                 );
                 if index == to_proccess_vec.len() {
                     index = 0;
                 }
             }
-            ParserOutput::Interface(name, _interface) => {
-                //@todo dont care for now
+            ParserOutput::Interface(_, interface) => {
+                let linearization = parser.linearize(&key)?;
+                processed_interfaces.push(merge_interface(interface, &linearization, &interfaces));
+
                 to_proccess_vec.remove(index);
-                to_proccess_map.remove(&name.clone());
+                to_proccess_map.remove(&key);
                 if index == to_proccess_vec.len() {
                     index = 0;
                 }
@@ -322,29 +416,38 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
 
                     // first we will expand all the `Library` calls
                     for call in function.calls.clone() {
-                        if let Call::Library(library_struct_name, library_function) = call {
-                            // @todo we are optimistic here
-                            let library_name =
-                                library_struct_name.split('_').next().unwrap_or_default();
-                            if to_proccess_map.contains_key(library_name) {
+                        if let Call::Library(library_function) = call {
+                            let library_name = (
+                                library_function.source_unit.clone(),
+                                library_function.contract.clone(),
+                            );
+                            if library_name == key {
+                                // functions of the same library are expanded below
+                                new_calls.push(Call::Write(
+                                    CallType::CallingFunction,
+                                    library_function.clone(),
+                                ));
+                                continue
+                            }
+                            if to_proccess_map.contains_key(&library_name) && can_wait {
                                 processed = false;
                                 break
                             }
 
                             if let Some(ParserOutput::Library(_, contract)) =
-                                outputs.get(library_name)
+                                outputs.get(&library_name)
                             {
                                 // find the function we are looking for
                                 let calls = contract
                                     .functions
                                     .iter()
                                     .filter(|function| {
-                                        function.header.name.clone() == library_function
+                                        function.header.signature() == library_function.member
                                     })
                                     .flat_map(|function| function.calls.clone())
                                     .collect::<Vec<_>>();
 
-                                new_calls.extend(calls);
+                                new_calls.extend(inline(calls));
                             }
                         } else {
                             new_calls.push(call);
@@ -361,12 +464,16 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
                 }
 
                 if !processed {
-                    continue;
+                    stalled += 1;
+                    index = (index + 1) % to_proccess_vec.len();
+                    continue
                 }
 
                 let mut filtered_functions = Vec::default();
                 for function in new_functions.clone() {
                     let mut filtered_function = function.clone();
+                    // recursive functions are expanded only once
+                    let mut expanded = Vec::default();
 
                     // in case of library we are only interested in `ReadStorage` and `WriteStorage`
                     // so we will filter now
@@ -375,27 +482,57 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
                     }) {
                         let mut filtered_calls = Vec::new();
 
-                        for call in filtered_calls.clone() {
+                        for call in filtered_function.calls.clone() {
                             match call {
-                                Call::ReadStorage(..) | Call::WriteStorage(..) => {
-                                    filtered_calls.push(call)
-                                }
-                                Call::Read(_, contract, function_name)
-                                | Call::Write(_, contract, function_name) => {
-                                    if contract == library.name {
+                                Call::ReadStorage(..)
+                                | Call::WriteStorage(..)
+                                | Call::WriteStorageField(..)
+                                | Call::External(..)
+                                | Call::Delegate
+                                | Call::Revert(..)
+                                | Call::AddressCall(..)
+                                | Call::Guard(..)
+                                | Call::OriginCheck
+                                | Call::SendValue(..)
+                                | Call::Create(..) => filtered_calls.push(call),
+                                // the order of the calls matters for the analysis of the flow
+                                Call::Branch
+                                | Call::Path
+                                | Call::Merge
+                                | Call::Loop
+                                | Call::EndLoop
+                                | Call::Return
+                                | Call::Enter
+                                | Call::Leave
+                                | Call::Placeholder => filtered_calls.push(call),
+                                // events emitted by libraries are attributed to the calling
+                                // contracts
+                                Call::Emit(..) => filtered_calls.push(call),
+                                // libraries do not inherit
+                                Call::Super(..) | Call::Base(..) => (),
+                                Call::Read(_, symbol) | Call::Write(_, symbol) => {
+                                    if expanded.contains(&symbol) {
+                                        continue
+                                    }
+                                    expanded.push(symbol.clone());
+
+                                    let contract = (symbol.source_unit, symbol.contract);
+                                    let function_name = symbol.member;
+
+                                    if contract == key {
                                         // if its the same contract we will look at already processed functions as
                                         // it may contain `Library` calls
                                         let calls = new_functions
                                             .clone()
                                             .iter()
                                             .filter(|function| {
-                                                function.header.name.clone() == function_name
+                                                function.header.signature() == function_name
                                             })
                                             .flat_map(|function| function.calls.clone())
                                             .collect::<Vec<_>>();
 
-                                        filtered_calls.extend(calls);
-                                    } else if to_proccess_map.contains_key(&contract) {
+                                        filtered_calls.extend(inline(calls));
+                                    } else if to_proccess_map.contains_key(&contract) && can_wait {
                                         processed = false;
                                         break
                                     } else if let Some(ParserOutput::Library(_, contract)) =
@@ -406,12 +543,12 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
                                             .functions
                                             .iter()
                                             .filter(|function| {
-                                                function.header.name.clone() == function_name
+                                                function.header.signature() == function_name
                                             })
                                             .flat_map(|function| function.calls.clone())
                                             .collect::<Vec<_>>();
 
-                                        filtered_calls.extend(calls);
+                                        filtered_calls.extend(inline(calls));
                                     }
                                 }
                                 Call::Library(..) => unreachable!("Should be processed by now"),
@@ -431,29 +568,32 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
                 }
 
                 if !processed {
+                    stalled += 1;
+                    index = (index + 1) % to_proccess_vec.len();
                     continue
                 }
 
                 for new_slot in library.slots.clone() {
-                    if let Some(fields) = slots_map.get(&new_slot.name) {
+                    if let Some(fields) = slots_map.get(&new_slot.symbol) {
                         let mut current_fields = new_slot.fields.clone();
                         current_fields.extend(fields.clone());
                         current_fields.sort();
                         current_fields.dedup();
 
-                        slots_map.insert(new_slot.name.clone(), current_fields);
+                        slots_map.insert(new_slot.symbol.clone(), current_fields);
                     } else {
-                        slots_map.insert(new_slot.name.clone(), new_slot.fields);
+                        slots_map.insert(new_slot.symbol.clone(), new_slot.fields);
                     }
                 }
 
                 let mut new_library = library.clone();
                 new_library.functions = filtered_functions;
 
+                stalled = 0;
                 to_proccess_vec.remove(index);
-                to_proccess_map.remove(&name.clone());
+                to_proccess_map.remove(&key);
                 outputs.insert(
-                    name.clone(),
+                    key.clone(),
                     ParserOutput::Library(name.clone(), new_library),
                 );
                 if index == to_proccess_vec.len() {
@@ -464,10 +604,2013 @@ fn run(path: &[String], options: &PoseidonOptions) -> Result<(), ParserError> {
         }
     }
 
+    // interfaces of the requested files are charted, as well as the ones the charted contracts
+    // implement or call
+    let charted_interfaces = processed_interfaces
+        .into_iter()
+        .filter(|interface| {
+            let requested = charted
+                .contains(&(interface.source_unit.clone(), interface.name.clone()))
+                && (options.contracts.is_empty() || options.contracts.contains(&interface.name));
+            let used = processed_vec.iter().any(|contract| {
+                contract.base.contains(&interface.name)
+                    || contract.functions.iter().any(|function| {
+                        function.calls.iter().any(|call| {
+                            matches!(
+                                call,
+                                Call::External(symbol, _)
+                                    if symbol.source_unit == interface.source_unit
+                                        && symbol.contract == interface.name
+                            )
+                        })
+                    })
+            });
+
+            !options.omitted.contains(&interface.name) && (requested || used)
+        })
+        .collect::<Vec<_>>();
+
     // now we pass processed vec to assembler
+    Ok(Analysis {
+        contracts: processed_vec,
+        interfaces: charted_interfaces,
+        slots: slots_map,
+        namespaces: parser.storage_namespaces(),
+    })
+}
+
+/// Remaps the calls of an inherited function or modifier to the inheriting contract
+///
+/// `function` the function of the base contract
+/// `new_contract` the inheriting contract
+fn remap_function(function: &Function, new_contract: &Contract) -> Function {
+    let mut new_function = function.clone();
+
+    new_function.calls = function
+        .calls
+        .iter()
+        .filter_map(|call| {
+            let new_call = match call {
+                Call::Read(call_type, _)
+                | Call::ReadStorage(call_type, _)
+                | Call::WriteStorage(call_type, _)
+                | Call::WriteStorageField(call_type, _, _)
+                | Call::Write(call_type, _) => {
+                    if let CallType::CallingStoragePointer = call_type {
+                        call.clone()
+                    } else {
+                        call.change_contract(new_contract)
+                    }
+                }
+                Call::Library(..)
+                | Call::External(..)
+                | Call::Delegate
+                | Call::Revert(..)
+                | Call::AddressCall(..)
+                | Call::Guard(..)
+                | Call::OriginCheck
+                | Call::SendValue(..)
+                | Call::Create(..) => call.clone(),
+                Call::Branch
+                | Call::Path
+                | Call::Merge
+                | Call::Loop
+                | Call::EndLoop
+                | Call::Return
+                | Call::Enter
+                | Call::Leave
+                | Call::Placeholder => call.clone(),
+                Call::Emit(..) => call.change_contract(new_contract),
+                // calls of the bases which could not be resolved are not charted
+                Call::Super(..) | Call::Base(..) => return None,
+            };
+            Some(new_call)
+        })
+        .collect();
+
+    new_function
+}
 
-    let output = poseidon::generate_mermaid(&processed_vec, &slots_map, options);
-    file_utils::write_mermaid(&output)?;
+/// Returns the calls of an inlined function body, e.g. of a library function, a `return`
+/// only leaves the inlined body
+///
+/// `calls` the calls of the function body
+fn inline(calls: Vec<Call>) -> Vec<Call> {
+    let mut inlined = vec![Call::Enter];
+    inlined.extend(calls);
+    inlined.push(Call::Leave);
+    inlined
+}
 
-    Ok(())
+/// Wraps the calls of a function with the calls of its modifiers, the function body runs at
+/// the placeholders `_` of the modifiers
+///
+/// `function` the function guarded by the modifiers
+/// `modifiers` the modifiers available in the contract, the most derived ones first
+fn apply_modifiers(function: &mut Function, modifiers: &[Function]) {
+    // the body runs at the placeholder of the last modifier, which runs at the placeholder
+    // of the previous one
+    let mut calls = std::mem::take(&mut function.calls);
+
+    for Expression::Modifier(modifier_name, modifier_args, modifier_arg_texts) in
+        function.header.modifiers.iter().rev()
+    {
+        let mut new_calls = modifier_args.clone();
+
+        // the same name might also be a call of a base constructor
+        if let Some(modifier) = modifiers
+            .iter()
+            .find(|modifier| &modifier.header.name == modifier_name)
+        {
+            // a `return` leaves only the wrapped body
+            let body = inline(calls);
+
+            for call in modifier.calls.iter() {
+                match call {
+                    Call::Placeholder => new_calls.extend(body.clone()),
+                    // guards of the modifier check its arguments, e.g. the role of
+                    // `onlyRole(MINTER_ROLE)`
+                    Call::Guard(guard) => {
+                        let argument = modifier
+                            .header
+                            .param_names
+                            .iter()
+                            .position(|name| name == guard)
+                            .and_then(|position| modifier_arg_texts.get(position));
+                        new_calls.push(Call::Guard(argument.unwrap_or(guard).clone()));
+                    }
+                    _ => new_calls.push(call.clone()),
+                }
+            }
+        } else {
+            new_calls.extend(calls);
+        }
+
+        calls = new_calls;
+    }
+
+    function.calls = calls;
+}
+
+/// Merges the members of the bases into a contract
+///
+/// Only the most derived definition of each function and modifier is kept, `super` and
+/// `Base.function` calls are resolved along the linearization
+///
+/// `contract` the contract as it was parsed
+/// `linearization` the C3 linearization of the contract
+/// `contracts` all the parsed contracts, keyed by the file defining them and their name
+fn merge_contract(
+    contract: &Contract,
+    linearization: &[(String, String)],
+    contracts: &HashMap<(String, String), Contract>,
+) -> Contract {
+    // interfaces and contracts which were not parsed are skipped
+    let bases = linearization
+        .iter()
+        .filter_map(|key| contracts.get(key))
+        .collect::<Vec<_>>();
+
+    let mut new_contract = contract.clone();
+    new_contract.linearization = linearization.iter().map(|(_, name)| name.clone()).collect();
+
+    for base in bases.iter().skip(1) {
+        new_contract.fields.append(&mut base.fields.clone());
+        new_contract.immutables.append(&mut base.immutables.clone());
+
+        for event in base.events.iter() {
+            if !new_contract.events.contains(event) {
+                new_contract.events.push(event.clone());
+            }
+        }
+    }
+
+    // modifiers resolve their `super` and `Base.function` calls in the contract defining them
+    let mut modifiers: Vec<Function> = Vec::default();
+    for (position, base) in bases.iter().enumerate() {
+        for modifier in base.modifiers.iter() {
+            if !modifiers
+                .iter()
+                .any(|known| known.header.name == modifier.header.name)
+            {
+                modifiers.push(resolve_function(modifier, position, &bases, &[], contract));
+            }
+        }
+    }
+
+    let mut functions: Vec<Function> = Vec::default();
+    for (position, base) in bases.iter().enumerate() {
+        for function in base.functions.iter() {
+            if !functions
+                .iter()
+                .any(|known| known.header.signature() == function.header.signature())
+            {
+                functions.push(resolve_function(
+                    function, position, &bases, &modifiers, contract,
+                ));
+            }
+        }
+    }
+
+    // constructors of the bases are executed before ours
+    new_contract.constructors = bases
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(position, base)| {
+            resolve_function(&base.constructor, position, &bases, &modifiers, contract)
+        })
+        .collect();
+    new_contract.constructor = new_contract
+        .constructors
+        .last()
+        .cloned()
+        .unwrap_or_default();
+
+    new_contract.modifiers = modifiers;
+    new_contract.functions = functions;
+
+    new_contract
+}
+
+/// Applies the modifiers of a function, resolves its `super` and `Base.function` calls
+/// and remaps it to the inheriting contract
+///
+/// `function` the function as it was parsed
+/// `position` the position of the contract defining the function in the linearization
+/// `bases` the linearized contracts
+/// `modifiers` the modifiers available in the contract, the most derived ones first
+/// `new_contract` the inheriting contract
+fn resolve_function(
+    function: &Function,
+    position: usize,
+    bases: &[&Contract],
+    modifiers: &[Function],
+    new_contract: &Contract,
+) -> Function {
+    let mut new_function = function.clone();
+    apply_modifiers(&mut new_function, modifiers);
+
+    let mut new_calls = Vec::default();
+
+    for call in new_function.calls.iter() {
+        match call {
+            Call::Super(symbol) => {
+                new_calls.extend(resolve_base_call(
+                    &symbol.member,
+                    position + 1,
+                    bases,
+                    modifiers,
+                    new_contract,
+                ))
+            }
+            Call::Base(symbol) => {
+                if let Some(base_position) = bases.iter().position(|base| {
+                    base.source_unit == symbol.source_unit && base.name == symbol.contract
+                }) {
+                    new_calls.extend(resolve_base_call(
+                        &symbol.member,
+                        base_position.max(position + 1),
+                        bases,
+                        modifiers,
+                        new_contract,
+                    ))
+                }
+            }
+            _ => new_calls.push(call.clone()),
+        }
+    }
+
+    new_function.calls = new_calls;
+
+    remap_function(&new_function, new_contract)
+}
+
+/// Returns the interface together with the functions declared by its bases
+///
+/// `interface` the interface as it was parsed
+/// `linearization` the interface and its bases, the most derived first
+/// `interfaces` the interfaces as they were parsed, keyed by the file defining them and their name
+fn merge_interface(
+    interface: &Interface,
+    linearization: &[(String, String)],
+    interfaces: &HashMap<(String, String), Interface>,
+) -> Interface {
+    let mut new_interface = interface.clone();
+
+    for base in linearization.iter().skip(1) {
+        let Some(base) = interfaces.get(base) else {
+            continue
+        };
+        for function_header in base.function_headers.iter() {
+            if !new_interface
+                .function_headers
+                .iter()
+                .any(|known| known.signature() == function_header.signature())
+            {
+                new_interface.function_headers.push(function_header.clone());
+            }
+        }
+    }
+
+    new_interface
+}
+
+/// Returns the calls of the first implementation of a function found in the linearization
+///
+/// `function_name` the signature of the called function
+/// `from` the position in the linearization where the lookup starts
+/// `bases` the linearized contracts
+/// `modifiers` the modifiers available in the contract, the most derived ones first
+/// `new_contract` the inheriting contract
+fn resolve_base_call(
+    function_name: &str,
+    from: usize,
+    bases: &[&Contract],
+    modifiers: &[Function],
+    new_contract: &Contract,
+) -> Vec<Call> {
+    bases
+        .iter()
+        .enumerate()
+        .skip(from)
+        .find_map(|(position, base)| {
+            base.functions
+                .iter()
+                .find(|function| function.header.signature() == function_name)
+                .map(|function| {
+                    resolve_function(function, position, bases, modifiers, new_contract).calls
+                })
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        path::PathBuf,
+        sync::atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+    };
+    use structures::Recipient;
+
+    /// Directory holding the sources of a single test, removed when the test ends
+    struct SourceDirectory(PathBuf);
+
+    impl SourceDirectory {
+        fn new() -> Self {
+            // tests of the same process run in parallel, each of them gets its own directory
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let directory = std::env::temp_dir().join(format!(
+                "sol2mermaid-{}-{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::create_dir_all(&directory).unwrap();
+
+            Self(directory)
+        }
+
+        /// Writes a source to the directory and returns its path
+        ///
+        /// `name` the path of the file relative to the directory
+        /// `source` the content of the file
+        fn write(&self, name: &str, source: &str) -> String {
+            let file = self.0.join(name);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(&file, source).unwrap();
+
+            file.to_string_lossy().to_string()
+        }
+    }
+
+    impl Drop for SourceDirectory {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Returns the contracts charted from Solidity sources, only the first source is requested,
+    /// the other ones are charted if it imports them
+    ///
+    /// `sources` the paths of the files relative to the directory of the test and their sources
+    fn analyze_sources(sources: &[(&str, &str)]) -> Analysis {
+        let directory = SourceDirectory::new();
+        let files = sources
+            .iter()
+            .map(|(name, source)| directory.write(name, source))
+            .collect::<Vec<_>>();
+
+        analyze(&files[..1], &PoseidonOptions::default()).unwrap()
+    }
+
+    /// Returns the contracts charted from a Solidity source
+    ///
+    /// `name` the name of the file the source is written to
+    /// `source` the Solidity source
+    fn analyze_source(name: &str, source: &str) -> Analysis {
+        analyze_sources(&[(name, source)])
+    }
+
+    /// Returns the calls of a function of a charted contract, without the markers of its control
+    /// flow and the reverts of its checks
+    ///
+    /// `analysis` the charted contracts
+    /// `contract` the name of the contract
+    /// `signature` the signature of the function, e.g. `transfer(address,uint256)`
+    fn calls(analysis: &Analysis, contract: &str, signature: &str) -> Vec<Call> {
+        analysis
+            .contracts
+            .iter()
+            .find(|charted| charted.name == contract)
+            .and_then(|charted| {
+                charted
+                    .functions
+                    .iter()
+                    .find(|function| function.header.signature() == signature)
+            })
+            .map(|function| {
+                function
+                    .calls
+                    .iter()
+                    .filter(|call| !call.is_flow_marker() && **call != Call::Revert(None))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_else(|| panic!("{contract}.{signature} is not charted"))
+    }
+
+    /// Returns the symbol of a member of a charted contract
+    ///
+    /// `analysis` the charted contracts
+    /// `contract` the name of the contract
+    /// `member` the name of the member
+    fn symbol(analysis: &Analysis, contract: &str, member: &str) -> Symbol {
+        let charted = analysis
+            .contracts
+            .iter()
+            .find(|charted| charted.name == contract)
+            .unwrap_or_else(|| panic!("{contract} is not charted"));
+
+        Symbol::new(&charted.source_unit, contract, member)
+    }
+
+    #[test]
+    fn writes_are_attributed_to_the_assigned_location() {
+        let analysis = analyze_source(
+            "Writes.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Writes {
+    struct User { uint256 balance; uint256 nonce; }
+    struct Config { uint256 fee; address admin; }
+    mapping(uint256 => uint256) ids;
+    mapping(address => User) users;
+    uint256 counter;
+    uint256[] keys;
+    Config config;
+    function setId(uint256 value) external { ids[counter] = value; }
+    function setNested(uint256 value) external { ids[keys[counter]] = value; }
+    function setFee(uint256 fee) external { config.fee = fee; }
+    function deposit(uint256 value) external { users[msg.sender].balance += value; }
+}
+"#,
+        );
+        let storage = |member: &str| symbol(&analysis, "Writes", member);
+
+        assert_eq!(
+            calls(&analysis, "Writes", "setId(uint256)"),
+            vec![
+                Call::WriteStorage(CallType::CallingStorage, storage("ids")),
+                Call::ReadStorage(CallType::CallingStorage, storage("counter")),
+            ]
+        );
+        assert_eq!(
+            calls(&analysis, "Writes", "setNested(uint256)"),
+            vec![
+                Call::WriteStorage(CallType::CallingStorage, storage("ids")),
+                Call::ReadStorage(CallType::CallingStorage, storage("keys")),
+                Call::ReadStorage(CallType::CallingStorage, storage("counter")),
+            ]
+        );
+        assert_eq!(
+            calls(&analysis, "Writes", "setFee(uint256)"),
+            vec![Call::WriteStorageField(
+                CallType::CallingStorage,
+                storage("config"),
+                String::from("fee")
+            )]
+        );
+        assert_eq!(
+            calls(&analysis, "Writes", "deposit(uint256)"),
+            vec![Call::WriteStorageField(
+                CallType::CallingStorage,
+                storage("users"),
+                String::from("balance")
+            )]
+        );
+    }
+
+    #[test]
+    fn overrides_and_super_follow_the_linearization() {
+        let analysis = analyze_source(
+            "Inherited.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Base {
+    enum State { Active, Paused }
+    State state;
+    uint256 constant LIMIT = 10;
+    uint256 total;
+    function touch() public virtual { state = State.Paused; }
+    function _update(uint256 value) internal virtual { total = value; }
+}
+contract Middle is Base {
+    uint256 middle;
+    function _update(uint256 value) internal virtual override { middle = value; super._update(value); }
+}
+contract Child is Base, Middle {
+    uint256 count;
+    modifier m() { require(state == Base.State.Active); require(count < Base.LIMIT); _; }
+    function _update(uint256 value) internal override { count = value; super._update(value); }
+    function run() external m { Base.touch(); }
+    function update(uint256 value) external { _update(value); }
+}
+"#,
+        );
+        let storage = |member: &str| symbol(&analysis, "Child", member);
+        let functions = analysis
+            .contracts
+            .iter()
+            .find(|charted| charted.name == "Child")
+            .map(|charted| {
+                charted
+                    .functions
+                    .iter()
+                    .map(|function| function.header.signature())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap();
+
+        // the overridden `_update` is charted once, reaching its bases through `super`
+        assert_eq!(
+            functions
+                .iter()
+                .filter(|function| *function == "_update(uint256)")
+                .count(),
+            1
+        );
+        assert_eq!(
+            calls(&analysis, "Child", "_update(uint256)"),
+            vec![
+                Call::WriteStorage(CallType::CallingStorage, storage("count")),
+                Call::WriteStorage(CallType::CallingStorage, storage("middle")),
+                Call::WriteStorage(CallType::CallingStorage, storage("total")),
+            ]
+        );
+        // enums and constants of a base used by a modifier are not base calls
+        assert_eq!(
+            calls(&analysis, "Child", "run()"),
+            vec![
+                Call::ReadStorage(CallType::CallingStorage, storage("state")),
+                Call::ReadStorage(CallType::CallingStorage, storage("count")),
+                Call::WriteStorage(CallType::CallingStorage, storage("state")),
+            ]
+        );
+    }
+
+    #[test]
+    fn overloads_are_selected_by_arity_and_argument_types() {
+        let analysis = analyze_source(
+            "Overloads.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Overloads {
+    uint256 a;
+    uint256 b;
+    uint256 c;
+    function set(uint256 value) public { a = value; }
+    function set(uint256 value, bytes memory data) public { b = value; }
+    function set(address account) public { c = 1; }
+    function one() external { set(1); }
+    function two() external { set(1, ""); }
+    function byType() external { set(msg.sender); }
+}
+"#,
+        );
+        let function = |member: &str| {
+            Call::Write(
+                CallType::CallingFunction,
+                symbol(&analysis, "Overloads", member),
+            )
+        };
+
+        assert_eq!(
+            calls(&analysis, "Overloads", "one()"),
+            vec![function("set(uint256)")]
+        );
+        assert_eq!(
+            calls(&analysis, "Overloads", "two()"),
+            vec![function("set(uint256,bytes)")]
+        );
+        assert_eq!(
+            calls(&analysis, "Overloads", "byType()"),
+            vec![function("set(address)")]
+        );
+
+        // each overload has its own node, labelled by its signature
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &PoseidonOptions::default(),
+        );
+        for signature in ["set(uint256)", "set(uint256,bytes)", "set(address)"] {
+            let id = symbol(&analysis, "Overloads", signature).mermaid_id("f");
+            assert!(
+                mermaid.contains(&format!("{id}[\"{signature}\"]")),
+                "{signature} has no node"
+            );
+        }
+    }
+
+    #[test]
+    fn writes_after_interactions_follow_branches_and_loops() {
+        let analysis = analyze_source(
+            "Flow.sol",
+            r#"
+pragma solidity ^0.8.0;
+interface IVault { function pull() external; function price() external view returns (uint256); }
+contract Oracle { uint256 public limit; }
+contract Flow {
+    Oracle oracle;
+    IVault vault;
+    uint256 total;
+    uint256 other;
+    error Failed();
+    function alternative(bool flag) external {
+        if (flag) { vault.pull(); } else { total = 1; }
+    }
+    function afterBranch(bool flag) external {
+        if (flag) { vault.pull(); }
+        total = 1;
+    }
+    function reverting(bool flag) external {
+        if (flag) { vault.pull(); revert Failed(); }
+        total = 1;
+    }
+    function revertingWithReason(bool flag) external {
+        if (flag) { vault.pull(); revert("no"); }
+        total = 1;
+    }
+    function revertingPlainly(bool flag) external {
+        if (flag) { vault.pull(); revert(); }
+        total = 1;
+    }
+    function requiring(bool flag) external { require(flag, "no"); assert(flag); }
+    function returning(bool flag) external {
+        if (flag) { vault.pull(); return; }
+        total = 1;
+    }
+    function afterReturn(bool flag) external { _pull(flag); total = 1; }
+    bool locked;
+    modifier nonReentrant() { require(!locked); locked = true; _; locked = false; }
+    function guarded(bool flag) external nonReentrant { if (flag) { return; } vault.pull(); }
+    function afterView() external { uint256 p = vault.price(); total = p + oracle.limit(); }
+    function _pull(bool flag) internal {
+        if (flag) { vault.pull(); return; }
+        other = 1;
+    }
+    function looping(uint256 count) external {
+        for (uint256 i = 0; i < count; i++) { other = i; vault.pull(); }
+    }
+    function viaInternal(uint256 value) external { vault.pull(); _store(value); }
+    function _store(uint256 value) internal { total = value; }
+    function inAssembly(bool flag) external {
+        vault.pull();
+        assembly { if flag { sstore(other.slot, 1) } }
+    }
+}
+"#,
+        );
+        let contract = analysis
+            .contracts
+            .iter()
+            .find(|charted| charted.name == "Flow")
+            .unwrap();
+        let written = |signature: &str| {
+            let function = contract
+                .functions
+                .iter()
+                .find(|function| function.header.signature() == signature)
+                .unwrap();
+            flow::writes_after_interactions(contract, function)
+                .into_iter()
+                .filter_map(|finding| {
+                    match finding.write {
+                        Call::WriteStorage(_, symbol) => Some(symbol.member),
+                        _ => None,
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(written("alternative(bool)").is_empty());
+        assert_eq!(written("afterBranch(bool)"), vec!["total"]);
+        assert!(written("reverting(bool)").is_empty());
+        assert!(written("revertingWithReason(bool)").is_empty());
+        assert!(written("revertingPlainly(bool)").is_empty());
+        // the failing side of `require` and `assert` reverts
+        let requiring = contract
+            .functions
+            .iter()
+            .find(|function| function.header.signature() == "requiring(bool)")
+            .unwrap();
+        assert_eq!(
+            requiring.calls,
+            vec![
+                Call::Branch,
+                Call::Revert(None),
+                Call::Path,
+                Call::Merge,
+                Call::Branch,
+                Call::Revert(None),
+                Call::Path,
+                Call::Merge,
+            ]
+        );
+        assert!(written("returning(bool)").is_empty());
+        // a `return` only leaves the called function
+        assert_eq!(written("afterReturn(bool)"), vec!["total"]);
+        // the unlock after the placeholder runs after the body, even if the body returns
+        let storage = |member: &str| symbol(&analysis, "Flow", member);
+        assert_eq!(written("guarded(bool)"), vec!["locked"]);
+        assert_eq!(
+            calls(&analysis, "Flow", "guarded(bool)"),
+            vec![
+                Call::ReadStorage(CallType::CallingStorage, storage("locked")),
+                Call::WriteStorage(CallType::CallingStorage, storage("locked")),
+                Call::ReadStorage(CallType::CallingStorage, storage("vault")),
+                Call::External(
+                    Symbol::new(&contract.source_unit, "IVault", "pull()"),
+                    false
+                ),
+                Call::WriteStorage(CallType::CallingStorage, storage("locked")),
+            ]
+        );
+        // `view` functions and getters do not change the state
+        assert!(written("afterView()").is_empty());
+        // the next iteration writes after the call of the previous one
+        assert_eq!(written("looping(uint256)"), vec!["other"]);
+        assert_eq!(written("viaInternal(uint256)"), vec!["total"]);
+        assert_eq!(written("inAssembly(bool)"), vec!["other"]);
+    }
+
+    #[test]
+    fn guards_only_grant_the_checked_account() {
+        let analysis = analyze_source(
+            "Vault.sol",
+            r#"
+pragma solidity ^0.8.0;
+interface IERC20 { function transferFrom(address, address, uint256) external returns (bool); }
+contract Vault {
+    address owner;
+    IERC20 token;
+    mapping(address => uint256) balances;
+    mapping(address => bool) banned;
+    error NotOwner();
+    function deposit(uint256 amount) external {
+        require(token.transferFrom(msg.sender, address(this), amount));
+        balances[msg.sender] += amount;
+    }
+    function notOwner(uint256 amount) external {
+        require(msg.sender != owner);
+        balances[msg.sender] = amount;
+    }
+    function nonZero(uint256 amount) external {
+        require(msg.sender != address(0));
+        balances[msg.sender] = amount;
+    }
+    function onlyOwnerRevert(address o) external {
+        if (msg.sender != owner) revert NotOwner();
+        owner = o;
+    }
+    function notBanned(uint256 amount) external {
+        require(!banned[msg.sender]);
+        balances[msg.sender] = amount;
+    }
+    function operator(uint256 amount) external {
+        require(isOperator(msg.sender) && !banned[msg.sender]);
+        balances[msg.sender] = amount;
+    }
+    function role(address o) external {
+        require(hasRole(keccak256("ADMIN"), msg.sender));
+        owner = o;
+    }
+    function isOperator(address) public view returns (bool) { return true; }
+    function hasRole(bytes32, address) public view returns (bool) { return true; }
+    function origin(address o) external {
+        require(tx.origin == owner);
+        owner = o;
+    }
+}
+"#,
+        );
+        let contract = analysis
+            .contracts
+            .iter()
+            .find(|charted| charted.name == "Vault")
+            .unwrap();
+        let function = |signature: &str| {
+            contract
+                .functions
+                .iter()
+                .find(|function| function.header.signature() == signature)
+                .unwrap()
+        };
+        let guards = |signature: &str| contract.guards(function(signature));
+
+        // calls receiving the sender, negated checks and literals do not grant any access
+        assert!(guards("deposit(uint256)").is_empty());
+        assert!(guards("notOwner(uint256)").is_empty());
+        assert!(guards("nonZero(uint256)").is_empty());
+        assert!(guards("notBanned(uint256)").is_empty());
+        assert_eq!(guards("onlyOwnerRevert(address)"), vec!["owner"]);
+        assert_eq!(guards("operator(uint256)"), vec!["isOperator"]);
+        assert_eq!(guards("role(address)"), vec!["keccak256(\"ADMIN\")"]);
+        // `tx.origin` is flagged instead of being a guard
+        assert!(guards("origin(address)").is_empty());
+        assert!(contract.checks_origin(function("origin(address)")));
+        assert!(!contract.checks_origin(function("onlyOwnerRevert(address)")));
+    }
+
+    #[test]
+    fn actors_are_named_by_the_access_checks() {
+        let analysis = analyze_source(
+            "Actors.sol",
+            r#"
+pragma solidity ^0.8.0;
+interface IERC20 { function transferFrom(address, address, uint256) external returns (bool); }
+contract Actors {
+    bytes32 constant MINTER_ROLE = keccak256("MINTER_ROLE");
+    address owner;
+    IERC20 token;
+    uint256 supply;
+    mapping(address => uint256) balances;
+    modifier onlyOwner() { require(msg.sender == owner); _; }
+    function deposit(uint256 amount) external {
+        require(token.transferFrom(msg.sender, address(this), amount));
+        balances[msg.sender] += amount;
+    }
+    function notOwner(uint256 amount) external {
+        require(msg.sender != owner);
+        balances[msg.sender] = amount;
+    }
+    function setOwner(address account) external onlyOwner { owner = account; }
+    function mint(uint256 amount) external {
+        require(hasRole(MINTER_ROLE, msg.sender));
+        supply += amount;
+    }
+    function operate(uint256 amount) external {
+        require(isOperator(msg.sender));
+        supply = amount;
+    }
+    function hasRole(bytes32, address) public view returns (bool) { return true; }
+    function isOperator(address) public view returns (bool) { return true; }
+}
+"#,
+        );
+        let options = PoseidonOptions {
+            actors: true,
+            ..Default::default()
+        };
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &options,
+        );
+        let actor = |name: &str| Symbol::new("", "Actors", name).mermaid_id("a");
+        let function = |signature: &str| symbol(&analysis, "Actors", signature).mermaid_id("f");
+
+        let actors = mermaid
+            .lines()
+            .filter(|line| line.ends_with(":::actor"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actors,
+            vec![
+                format!("{}[\"Anyone\"]:::actor", actor("Anyone")),
+                format!("{}[\"MINTER_ROLE\"]:::actor", actor("MINTER_ROLE")),
+                format!("{}[\"Operator\"]:::actor", actor("Operator")),
+                format!("{}[\"Owner\"]:::actor", actor("Owner")),
+            ]
+        );
+        for (name, signature) in [
+            ("Anyone", "deposit(uint256)"),
+            ("Anyone", "notOwner(uint256)"),
+            ("Owner", "setOwner(address)"),
+            ("MINTER_ROLE", "mint(uint256)"),
+            ("Operator", "operate(uint256)"),
+        ] {
+            let edge = format!("{} --> {}", actor(name), function(signature));
+            assert!(
+                mermaid.lines().any(|line| line == edge),
+                "{name} cannot call {signature}"
+            );
+        }
+    }
+
+    #[test]
+    fn assembly_sends_value_by_call_and_selfdestruct() {
+        let analysis = analyze_source(
+            "Assembly.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Assembly {
+    address treasury;
+    function pay(address to, uint256 amount) external {
+        assembly { pop(call(gas(), to, amount, 0, 0, 0, 0)) }
+    }
+    function ping(address to) external {
+        assembly { pop(call(gas(), to, 0, 0, 0, 0, 0)) }
+    }
+    function refund() external {
+        assembly { pop(call(gas(), caller(), callvalue(), 0, 0, 0, 0)) }
+    }
+    function close() external {
+        assembly { selfdestruct(sload(treasury.slot)) }
+    }
+}
+"#,
+        );
+        let sent = |signature: &str| {
+            calls(&analysis, "Assembly", signature)
+                .into_iter()
+                .filter(|call| matches!(call, Call::SendValue(..)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sent("pay(address,uint256)"),
+            vec![Call::SendValue(
+                String::from("call"),
+                Recipient::Parameter(String::from("to"))
+            )]
+        );
+        assert!(sent("ping(address)").is_empty());
+        assert_eq!(
+            sent("refund()"),
+            vec![Call::SendValue(String::from("call"), Recipient::Sender)]
+        );
+        assert_eq!(
+            sent("close()"),
+            vec![Call::SendValue(
+                String::from("selfdestruct"),
+                Recipient::Storage(String::from("treasury"))
+            )]
+        );
+    }
+
+    #[test]
+    fn custom_errors_are_resolved_in_their_scopes() {
+        let analysis = analyze_source(
+            "Errors.sol",
+            r#"
+pragma solidity ^0.8.0;
+error Failed(uint256 code);
+interface IVault { function pull() external returns (uint256); }
+contract ErrA {
+    error Unauthorized();
+    function a() external { revert Unauthorized(); }
+    function required(bool flag) external { require(flag, Unauthorized()); }
+    function qualified() external { revert ErrB.Unauthorized(msg.sender, 1); }
+    function fileLevel() external { revert Failed(1); }
+    function undeclared() external { revert Missing(1); }
+    function undeclaredWithoutArgs() external { revert Gone(); }
+}
+contract ErrB {
+    error Unauthorized(address account, uint256 amount);
+    function b() external { revert Unauthorized(msg.sender, 1); }
+}
+contract ErrC is ErrB {
+    IVault vault;
+    uint256 total;
+    uint256 caught;
+    function c() external { revert Unauthorized(msg.sender, 2); }
+    function tries() external {
+        try vault.pull() returns (uint256 total) {
+            total = 1;
+        } catch Error(string memory) {
+            caught = 1;
+        } catch (bytes memory) {
+            revert Failed(2);
+        }
+        total = 2;
+    }
+}
+"#,
+        );
+        let errors = |contract: &str, signature: &str| {
+            let charted = analysis
+                .contracts
+                .iter()
+                .find(|charted| charted.name == contract)
+                .unwrap();
+            let function = charted
+                .functions
+                .iter()
+                .find(|function| function.header.signature() == signature)
+                .unwrap();
+            charted.errors(function)
+        };
+
+        assert_eq!(errors("ErrA", "a()"), vec!["Unauthorized()"]);
+        assert_eq!(errors("ErrA", "required(bool)"), vec!["Unauthorized()"]);
+        assert_eq!(
+            errors("ErrA", "qualified()"),
+            vec!["Unauthorized(address,uint256)"]
+        );
+        assert_eq!(errors("ErrA", "fileLevel()"), vec!["Failed(uint256)"]);
+        // the parameters of errors which were not declared are not known
+        assert_eq!(errors("ErrA", "undeclared()"), vec!["Missing"]);
+        assert_eq!(errors("ErrA", "undeclaredWithoutArgs()"), vec!["Gone()"]);
+        assert_eq!(errors("ErrB", "b()"), vec!["Unauthorized(address,uint256)"]);
+        assert_eq!(errors("ErrC", "c()"), vec!["Unauthorized(address,uint256)"]);
+        assert_eq!(errors("ErrC", "tries()"), vec!["Failed(uint256)"]);
+
+        // the returned `total` shadows the storage only in the success block
+        let storage = |member: &str| symbol(&analysis, "ErrC", member);
+        let tries = calls(&analysis, "ErrC", "tries()");
+        assert_eq!(
+            tries
+                .iter()
+                .filter(|call| matches!(call, Call::WriteStorage(..)))
+                .cloned()
+                .collect::<Vec<_>>(),
+            vec![
+                Call::WriteStorage(CallType::CallingStorage, storage("caught")),
+                Call::WriteStorage(CallType::CallingStorage, storage("total")),
+            ]
+        );
+    }
+
+    #[test]
+    fn calls_are_resolved_through_typed_receivers() {
+        let analysis = analyze_source(
+            "Receivers.sol",
+            r#"
+pragma solidity ^0.8.0;
+interface IVault { function pull() external; }
+interface IToken { function mint(uint256 amount) external; }
+contract Registry { mapping(uint256 => IVault) public vaults; }
+contract Receivers {
+    struct Settings { IToken token; }
+    IVault[] vaults;
+    Settings settings;
+    Registry registry;
+    function viaArray(uint256 i) external { vaults[i].pull(); }
+    function viaStruct() external { settings.token.mint(1); }
+    function viaReturn() external { getVault().pull(); }
+    function viaGetter(uint256 id) external { registry.vaults(id).pull(); }
+    function viaThis() external { this.viaStruct(); }
+    function viaConversion(address vault) external { IVault(vault).pull(); }
+    function getVault() public view returns (IVault) { return vaults[0]; }
+}
+"#,
+        );
+        let source_unit = &analysis.contracts[0].source_unit;
+        let externals = |signature: &str| {
+            calls(&analysis, "Receivers", signature)
+                .into_iter()
+                .filter(|call| matches!(call, Call::External(..)))
+                .collect::<Vec<_>>()
+        };
+        let external = |contract: &str, member: &str| {
+            Call::External(Symbol::new(source_unit, contract, member), false)
+        };
+
+        assert_eq!(
+            externals("viaArray(uint256)"),
+            vec![external("IVault", "pull()")]
+        );
+        assert_eq!(
+            externals("viaStruct()"),
+            vec![external("IToken", "mint(uint256)")]
+        );
+        assert_eq!(externals("viaReturn()"), vec![external("IVault", "pull()")]);
+        assert_eq!(
+            externals("viaGetter(uint256)"),
+            vec![
+                Call::External(Symbol::new(source_unit, "Registry", "vaults"), true),
+                external("IVault", "pull()"),
+            ]
+        );
+        assert_eq!(
+            externals("viaThis()"),
+            vec![external("Receivers", "viaStruct()")]
+        );
+        assert_eq!(
+            externals("viaConversion(address)"),
+            vec![external("IVault", "pull()")]
+        );
+    }
+
+    #[test]
+    fn events_of_libraries_are_emitted_by_the_calling_contract() {
+        let analysis = analyze_source(
+            "Diamond.sol",
+            r#"
+pragma solidity ^0.8.0;
+library LibDiamond {
+    bytes32 constant POSITION = keccak256("diamond.standard.diamond.storage");
+    struct DiamondStorage { address owner; }
+    event DiamondCut(address init);
+    event OwnershipTransferred(address owner);
+    function diamondStorage() internal pure returns (DiamondStorage storage ds) {
+        bytes32 position = POSITION;
+        assembly { ds.slot := position }
+    }
+    function diamondCut(address init) internal { emit DiamondCut(init); }
+    function setOwner(address owner) internal {
+        diamondStorage().owner = owner;
+        _announce(owner);
+    }
+    function _announce(address owner) private { emit OwnershipTransferred(owner); }
+}
+contract Diamond {
+    function cut(address init) external { LibDiamond.diamondCut(init); }
+    function transferOwnership(address owner) external { LibDiamond.setOwner(owner); }
+}
+"#,
+        );
+        let events = |signature: &str| {
+            calls(&analysis, "Diamond", signature)
+                .into_iter()
+                .filter(|call| matches!(call, Call::Emit(..)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            events("cut(address)"),
+            vec![Call::Emit(symbol(&analysis, "Diamond", "DiamondCut"))]
+        );
+        // events of the functions the library calls are attributed too
+        assert_eq!(
+            events("transferOwnership(address)"),
+            vec![Call::Emit(symbol(
+                &analysis,
+                "Diamond",
+                "OwnershipTransferred"
+            ))]
+        );
+
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &PoseidonOptions::default(),
+        );
+        let event = symbol(&analysis, "Diamond", "DiamondCut").mermaid_id("e");
+        assert!(mermaid.contains(&format!("{event}>DiamondCut]:::event")));
+    }
+
+    #[test]
+    fn contracts_with_the_same_name_are_kept_apart() {
+        let analysis = analyze_sources(&[
+            (
+                "Router.sol",
+                r#"
+pragma solidity ^0.8.0;
+import {Pool_V2} from "./a/Pool.sol";
+import {Strategy_V2} from "./b/Strategy.sol";
+contract Router_V2 is Pool_V2 {
+    Strategy_V2 strategy;
+    function run() external { strategy.migrate(); }
+}
+"#,
+            ),
+            (
+                "a/Pool.sol",
+                r#"
+pragma solidity ^0.8.0;
+import "./Vault.sol";
+contract Pool_V2 is Vault_V2 {}
+"#,
+            ),
+            (
+                "a/Vault.sol",
+                r#"
+pragma solidity ^0.8.0;
+contract Vault_V2 {
+    uint256 balance;
+    function deposit() public { balance += 1; }
+}
+"#,
+            ),
+            (
+                "b/Strategy.sol",
+                r#"
+pragma solidity ^0.8.0;
+import "./Vault.sol";
+contract Strategy_V2 is Vault_V2 {
+    function migrate() external { deposit(); }
+}
+"#,
+            ),
+            (
+                "b/Vault.sol",
+                r#"
+pragma solidity ^0.8.0;
+contract Vault_V2 {
+    address owner;
+    function deposit() public { owner = msg.sender; }
+}
+"#,
+            ),
+        ]);
+        let router = analysis
+            .contracts
+            .iter()
+            .find(|contract| contract.name == "Router_V2")
+            .unwrap();
+
+        // the base is the `Vault_V2` imported by `Pool_V2`, not the one of `Strategy_V2`
+        assert_eq!(router.linearization, ["Router_V2", "Pool_V2", "Vault_V2"]);
+        assert_eq!(
+            router
+                .fields
+                .iter()
+                .map(|field| field.name.as_str())
+                .collect::<Vec<_>>(),
+            ["strategy", "balance"]
+        );
+        assert_eq!(
+            calls(&analysis, "Router_V2", "deposit()"),
+            vec![Call::WriteStorage(
+                CallType::CallingStorage,
+                symbol(&analysis, "Router_V2", "balance")
+            )]
+        );
+
+        let migrate = calls(&analysis, "Router_V2", "run()")
+            .into_iter()
+            .find_map(|call| {
+                match call {
+                    Call::External(symbol, _) => Some(symbol),
+                    _ => None,
+                }
+            })
+            .expect("strategy.migrate() is not an external call");
+        assert!(migrate.source_unit.ends_with("b/Strategy.sol"));
+        assert_eq!(migrate.contract, "Strategy_V2");
+    }
+
+    #[test]
+    fn modifiers_are_folded_into_the_functions_they_guard() {
+        let analysis = analyze_source(
+            "Guarded.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Ownable {
+    address owner;
+    modifier onlyOwner() { require(msg.sender == owner); _; }
+}
+contract Guarded is Ownable {
+    uint256 status;
+    uint256 fee;
+    modifier nonReentrant() { status = 2; _; status = 1; }
+    function setFee(uint256 newFee) external onlyOwner nonReentrant { fee = newFee; }
+}
+"#,
+        );
+        let read = |field: &str| {
+            Call::ReadStorage(
+                CallType::CallingStorage,
+                symbol(&analysis, "Guarded", field),
+            )
+        };
+        let write = |field: &str| {
+            Call::WriteStorage(
+                CallType::CallingStorage,
+                symbol(&analysis, "Guarded", field),
+            )
+        };
+
+        // the inherited modifier runs first, the body runs at the placeholder of the last one
+        assert_eq!(
+            calls(&analysis, "Guarded", "setFee(uint256)"),
+            vec![
+                read("owner"),
+                Call::Guard(String::from("owner")),
+                write("status"),
+                write("fee"),
+                write("status"),
+            ]
+        );
+    }
+
+    #[test]
+    fn deployment_chains_constructors_and_initializers() {
+        let analysis = analyze_source(
+            "Token.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Base {
+    address immutable factory;
+    uint256 created;
+    constructor() { factory = msg.sender; created = block.timestamp; }
+}
+contract Token is Base {
+    uint256 supply;
+    address admin;
+    constructor(uint256 initial) { supply = initial; }
+    modifier initializer() { _; }
+    function initialize(address newAdmin) external initializer { admin = newAdmin; }
+}
+"#,
+        );
+        let token = analysis
+            .contracts
+            .iter()
+            .find(|contract| contract.name == "Token")
+            .unwrap();
+
+        // the most base constructor runs first
+        assert_eq!(
+            token
+                .constructors
+                .iter()
+                .map(|constructor| constructor.header.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Base", "Token"]
+        );
+
+        let mermaid = poseidon::generate_deployment_mermaid(&analysis.contracts);
+        let node = |kind: &str, member: &str| symbol(&analysis, "Token", member).mermaid_id(kind);
+
+        assert!(mermaid.contains(&format!(
+            "{} ==> {}\n",
+            node("c", "Base"),
+            node("c", "Token")
+        )));
+        assert!(mermaid.contains(&format!(
+            "{} --> {}\n",
+            node("c", "Base"),
+            node("i", "factory")
+        )));
+        assert!(mermaid.contains(&format!(
+            "{} --> {}\n",
+            node("c", "Base"),
+            node("s", "created")
+        )));
+        assert!(mermaid.contains(&format!(
+            "{} --> {}\n",
+            node("c", "Token"),
+            node("s", "supply")
+        )));
+        assert!(mermaid.contains(&format!(
+            "{}[initialize]:::initializer\n",
+            node("f", "initialize(address)")
+        )));
+        assert!(mermaid.contains(&format!(
+            "{} --> {}\n",
+            node("f", "initialize(address)"),
+            node("s", "admin")
+        )));
+    }
+
+    #[test]
+    fn file_level_items_are_charted_like_a_library() {
+        let analysis = analyze_source(
+            "Main.sol",
+            r#"
+pragma solidity ^0.8.0;
+bytes32 constant POSITION = keccak256("example.main");
+struct MainStorage { uint256 total; address owner; }
+error Unauthorized();
+function _storage() pure returns (MainStorage storage s) {
+    bytes32 position = POSITION;
+    assembly { s.slot := position }
+}
+function _bump(uint256 amount) { _storage().total += amount; }
+contract Main {
+    function deposit(uint256 amount) external {
+        if (amount == 0) revert Unauthorized();
+        _bump(amount);
+    }
+    function owner() external view returns (address) { return _storage().owner; }
+}
+"#,
+        );
+        // the file scope is named after the file
+        let file = symbol(&analysis, "Main", "deposit(uint256)").source_unit;
+        let slot = Symbol::new("", "example.main", "MainStorage");
+
+        assert_eq!(
+            calls(&analysis, "Main", "deposit(uint256)"),
+            vec![
+                Call::Revert(Some(Symbol::new(&file, &file, "Unauthorized()"))),
+                Call::WriteStorage(CallType::CallingStoragePointer, slot.field("total")),
+            ]
+        );
+        assert_eq!(
+            calls(&analysis, "Main", "owner()"),
+            vec![Call::ReadStorage(
+                CallType::CallingStoragePointer,
+                slot.field("owner")
+            )]
+        );
+        assert_eq!(analysis.slots[&slot], ["owner", "total"]);
+        assert_eq!(analysis.namespaces, ["example.main"]);
+    }
+
+    #[test]
+    fn using_directives_bind_method_calls_to_libraries() {
+        let analysis = analyze_source(
+            "Holders.sol",
+            r#"
+pragma solidity ^0.8.0;
+interface IERC20 { function transfer(address to, uint256 amount) external returns (bool); }
+library SafeERC20 {
+    function safeTransfer(IERC20 token, address to, uint256 amount) internal {
+        require(token.transfer(to, amount));
+    }
+}
+library Set {
+    struct AddressSet { address[] values; }
+    function add(AddressSet storage set, address value) internal { set.values.push(value); }
+}
+library Math {
+    function double(uint256 value) internal pure returns (uint256) { return value * 2; }
+}
+using Math for uint256 global;
+contract Holders {
+    using Set for Set.AddressSet;
+    using SafeERC20 for IERC20;
+    Set.AddressSet _holders;
+    IERC20 token;
+    function join() external { _holders.add(msg.sender); }
+    function pay(address to, uint256 amount) external { token.safeTransfer(to, amount.double()); }
+}
+"#,
+        );
+
+        // the library writes through the storage pointer to `_holders`
+        assert!(
+            calls(&analysis, "Holders", "join()").contains(&Call::WriteStorage(
+                CallType::CallingStorage,
+                symbol(&analysis, "Holders", "_holders")
+            ))
+        );
+        // the global directive of `uint256` does not touch the storage
+        assert_eq!(
+            calls(&analysis, "Holders", "pay(address,uint256)"),
+            vec![
+                Call::ReadStorage(
+                    CallType::CallingStorage,
+                    symbol(&analysis, "Holders", "token")
+                ),
+                Call::External(
+                    Symbol::new(
+                        &symbol(&analysis, "Holders", "token").source_unit,
+                        "IERC20",
+                        "transfer(address,uint256)"
+                    ),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn locals_and_parameters_shadow_storage() {
+        let analysis = analyze_source(
+            "Shadow.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Shadow {
+    address owner;
+    uint256 amount;
+    uint256 value;
+    uint256 i;
+    uint256 total;
+    function set(address owner) external returns (uint256 value) {
+        uint256 amount = 1;
+        for (uint256 i = 0; i < 3; i++) { amount += i; }
+        value = amount;
+        assembly { let total := 5 total := add(total, 1) }
+        owner = msg.sender;
+        total = value;
+    }
+    function reset() external {
+        { uint256 amount = 2; amount += 1; }
+        amount = 0;
+    }
+}
+"#,
+        );
+        let write = |field: &str| {
+            Call::WriteStorage(CallType::CallingStorage, symbol(&analysis, "Shadow", field))
+        };
+
+        // parameters, locals, return and loop variables and Yul bindings are not storage
+        assert_eq!(
+            calls(&analysis, "Shadow", "set(address)"),
+            vec![write("total")]
+        );
+        // locals end with their block
+        assert_eq!(calls(&analysis, "Shadow", "reset()"), vec![write("amount")]);
+    }
+
+    #[test]
+    fn interfaces_are_linked_to_their_callers_and_implementations() {
+        let analysis = analyze_source(
+            "Router.sol",
+            r#"
+pragma solidity ^0.8.0;
+interface IVault {
+    function deposit() external;
+    function total() external view returns (uint256);
+}
+contract Vault is IVault {
+    uint256 public total;
+    function deposit() external { total += 1; }
+}
+contract Router {
+    IVault vault;
+    function run() external { vault.deposit(); }
+}
+"#,
+        );
+        let options = PoseidonOptions {
+            link_implementations: true,
+            ..Default::default()
+        };
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &options,
+        );
+        let file = symbol(&analysis, "Vault", "total").source_unit;
+        let interface = |member: &str| Symbol::new(&file, "IVault", member).mermaid_id("f");
+        let deposit = symbol(&analysis, "Vault", "deposit()").mermaid_id("f");
+        let run = symbol(&analysis, "Router", "run()").mermaid_id("f");
+
+        // all the functions of the interface are charted
+        assert!(mermaid.contains(&format!("{}[deposit]:::external\n", interface("deposit()"))));
+        assert!(mermaid.contains(&format!(
+            "{}[total]:::external_view\n",
+            interface("total()")
+        )));
+        assert!(mermaid.contains("Vault -.->|implements| IVault\n"));
+        assert!(mermaid.contains(&format!("{run} --> {}\n", interface("deposit()"))));
+        assert!(mermaid.contains(&format!(
+            "{} -. implementation .-> {deposit}\n",
+            interface("deposit()")
+        )));
+    }
+
+    #[test]
+    fn assembly_accesses_slots_and_transient_storage() {
+        let analysis = analyze_source(
+            "Yul.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Yul {
+    uint256 counter;
+    uint256 locked;
+    function bump() external {
+        assembly {
+            let current := sload(counter.slot)
+            sstore(counter.slot, add(current, 1))
+        }
+    }
+    function lock() external {
+        assembly {
+            if tload(locked.slot) { revert(0, 0) }
+            tstore(locked.slot, 1)
+        }
+    }
+}
+"#,
+        );
+        let counter = symbol(&analysis, "Yul", "counter");
+        let locked = symbol(&analysis, "Yul", "locked");
+
+        assert_eq!(
+            calls(&analysis, "Yul", "bump()"),
+            vec![
+                Call::ReadStorage(CallType::CallingStorage, counter.clone()),
+                Call::WriteStorage(CallType::CallingStorage, counter),
+            ]
+        );
+        assert_eq!(
+            calls(&analysis, "Yul", "lock()"),
+            vec![
+                Call::ReadStorage(CallType::CallingTransientStorage, locked.clone()),
+                Call::WriteStorage(CallType::CallingTransientStorage, locked.clone()),
+            ]
+        );
+
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &PoseidonOptions::default(),
+        );
+        assert!(mermaid.contains(&format!(
+            "{} -->|transient| {}\n",
+            symbol(&analysis, "Yul", "lock()").mermaid_id("f"),
+            locked.mermaid_id("s")
+        )));
+    }
+
+    #[test]
+    fn storage_positions_are_grouped_by_namespace() {
+        let analysis = analyze_sources(&[
+            (
+                "Facets.sol",
+                r#"
+pragma solidity ^0.8.0;
+import "./LibDiamond.sol";
+contract OwnershipFacet {
+    function transferOwnership(address owner) external {
+        LibDiamond.diamondStorage().owner = owner;
+    }
+}
+contract PauseFacet {
+    function pause() external {
+        LibDiamond.DiamondStorage storage ds = LibDiamond.diamondStorage();
+        ds.paused = true;
+    }
+}
+contract TokenFacet {
+    struct TokenStorage { uint256 supply; }
+    bytes32 private constant TOKEN_STORAGE =
+        keccak256(abi.encode(uint256(keccak256("example.token")) - 1)) & ~bytes32(uint256(0xff));
+    function _getTokenStorage() private pure returns (TokenStorage storage $) {
+        assembly { $.slot := TOKEN_STORAGE }
+    }
+    function mint(uint256 amount) external { _getTokenStorage().supply += amount; }
+}
+"#,
+            ),
+            (
+                "LibDiamond.sol",
+                r#"
+pragma solidity ^0.8.0;
+library LibDiamond {
+    bytes32 constant DIAMOND_STORAGE_POSITION = keccak256("diamond.standard.diamond.storage");
+    struct DiamondStorage { address owner; bool paused; }
+    function diamondStorage() internal pure returns (DiamondStorage storage ds) {
+        bytes32 position = DIAMOND_STORAGE_POSITION;
+        assembly { ds.slot := position }
+    }
+}
+"#,
+            ),
+        ]);
+        let diamond = Symbol::new("", "diamond.standard.diamond.storage", "DiamondStorage");
+        let token = Symbol::new("", "example.token", "TokenStorage");
+
+        // facets share the namespace named after the position
+        assert_eq!(
+            calls(&analysis, "OwnershipFacet", "transferOwnership(address)"),
+            vec![Call::WriteStorage(
+                CallType::CallingStoragePointer,
+                diamond.field("owner")
+            )]
+        );
+        assert_eq!(
+            calls(&analysis, "PauseFacet", "pause()"),
+            vec![Call::WriteStorage(
+                CallType::CallingStoragePointer,
+                diamond.field("paused")
+            )]
+        );
+        // ERC-7201 positions are named after their namespace
+        assert!(
+            calls(&analysis, "TokenFacet", "mint(uint256)").contains(&Call::WriteStorage(
+                CallType::CallingStoragePointer,
+                token.field("supply")
+            ))
+        );
+        assert_eq!(
+            analysis.namespaces,
+            ["diamond.standard.diamond.storage", "example.token"]
+        );
+
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &PoseidonOptions::default(),
+        );
+        let namespace = Symbol::new("", "diamond.standard.diamond.storage", "").mermaid_id("g");
+        assert_eq!(
+            mermaid
+                .matches(&format!(
+                    "subgraph {namespace}[diamond.standard.diamond.storage]\n"
+                ))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn storage_pointers_returned_by_functions_are_followed() {
+        let analysis = analyze_source(
+            "Upgradeable.sol",
+            r#"
+pragma solidity ^0.8.0;
+library LibVault {
+    struct Layout { uint256 balance; address owner; }
+    bytes32 constant POSITION = keccak256("example.vault");
+    function layout() internal pure returns (Layout storage l) {
+        bytes32 position = POSITION;
+        assembly { l.slot := position }
+    }
+}
+contract Upgradeable {
+    struct Config { uint256 fee; }
+    Config config;
+    function s() internal view returns (Config storage) { return config; }
+    function deposit() external {
+        LibVault.Layout storage l = LibVault.layout();
+        l.balance += 1;
+    }
+    function owner() external view returns (address) { return LibVault.layout().owner; }
+    function setFee(uint256 fee) external { s().fee = fee; }
+}
+"#,
+        );
+        let layout = Symbol::new("", "example.vault", "Layout");
+
+        // through a local initialized by the function and through the returned value
+        assert_eq!(
+            calls(&analysis, "Upgradeable", "deposit()"),
+            vec![Call::WriteStorage(
+                CallType::CallingStoragePointer,
+                layout.field("balance")
+            )]
+        );
+        assert_eq!(
+            calls(&analysis, "Upgradeable", "owner()"),
+            vec![Call::ReadStorage(
+                CallType::CallingStoragePointer,
+                layout.field("owner")
+            )]
+        );
+        assert!(
+            calls(&analysis, "Upgradeable", "setFee(uint256)").contains(&Call::WriteStorage(
+                CallType::CallingStoragePointer,
+                symbol(&analysis, "Upgradeable", "Config.fee")
+            ))
+        );
+    }
+
+    #[test]
+    fn writes_through_aliases_reach_the_aliased_storage() {
+        let analysis = analyze_source(
+            "Bank.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Bank {
+    struct User { uint256 balance; uint256 nonce; }
+    mapping(uint256 => User) users;
+    User[] queue;
+    User admin;
+    function credit(uint256 id, uint256 amount) external {
+        User storage u = users[id];
+        u.balance += amount;
+        u = queue[0];
+        u.nonce += 1;
+    }
+    function touch() external {
+        User storage a = admin;
+        a.nonce = 1;
+    }
+}
+"#,
+        );
+        let read = |field: &str| {
+            Call::ReadStorage(CallType::CallingStorage, symbol(&analysis, "Bank", field))
+        };
+        let write = |field: &str, path: &str| {
+            Call::WriteStorageField(
+                CallType::CallingStorage,
+                symbol(&analysis, "Bank", field),
+                path.to_string(),
+            )
+        };
+
+        // moving the pointer to another element only reads the storage
+        assert_eq!(
+            calls(&analysis, "Bank", "credit(uint256,uint256)"),
+            vec![
+                read("users"),
+                write("users", "balance"),
+                read("queue"),
+                write("queue", "nonce"),
+            ]
+        );
+        assert_eq!(
+            calls(&analysis, "Bank", "touch()"),
+            vec![read("admin"), write("admin", "nonce")]
+        );
+    }
+
+    #[test]
+    fn mutators_and_tuples_write_the_storage() {
+        let analysis = analyze_source(
+            "Queue.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Queue {
+    uint256[] items;
+    bytes data;
+    uint256 head;
+    uint256 tail;
+    struct Entry { uint256[] ids; }
+    Entry entry;
+    function pair() internal view returns (uint256, uint256) { return (1, 2); }
+    function enqueue(uint256 item) external { items.push(item); data.push(); entry.ids.push(item); }
+    function dequeue() external { items.pop(); delete items[0]; }
+    function reset() external { (head, tail) = pair(); }
+    function count() external view returns (uint256) { return items.length; }
+}
+"#,
+        );
+        let write = |field: &str| {
+            Call::WriteStorage(CallType::CallingStorage, symbol(&analysis, "Queue", field))
+        };
+
+        assert_eq!(
+            calls(&analysis, "Queue", "enqueue(uint256)"),
+            vec![
+                write("items"),
+                write("data"),
+                Call::WriteStorageField(
+                    CallType::CallingStorage,
+                    symbol(&analysis, "Queue", "entry"),
+                    String::from("ids")
+                ),
+            ]
+        );
+        assert_eq!(
+            calls(&analysis, "Queue", "dequeue()"),
+            vec![write("items"), write("items")]
+        );
+        assert_eq!(
+            calls(&analysis, "Queue", "reset()"),
+            vec![
+                Call::Read(
+                    CallType::CallingFunction,
+                    symbol(&analysis, "Queue", "pair()")
+                ),
+                write("head"),
+                write("tail"),
+            ]
+        );
+        // the length is only read
+        assert_eq!(
+            calls(&analysis, "Queue", "count()"),
+            vec![Call::ReadStorage(
+                CallType::CallingStorage,
+                symbol(&analysis, "Queue", "items")
+            )]
+        );
+    }
+
+    #[test]
+    fn receive_and_fallback_are_entry_points() {
+        let analysis = analyze_source(
+            "Proxy.sol",
+            r#"
+pragma solidity ^0.8.0;
+contract Proxy {
+    address implementation;
+    uint256 received;
+    receive() external payable { received += msg.value; }
+    fallback() external payable {
+        address target = implementation;
+        assembly {
+            calldatacopy(0, 0, calldatasize())
+            let result := delegatecall(gas(), target, 0, calldatasize(), 0, 0)
+            returndatacopy(0, 0, returndatasize())
+            switch result case 0 { revert(0, returndatasize()) } default { return(0, returndatasize()) }
+        }
+    }
+}
+"#,
+        );
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &PoseidonOptions::default(),
+        );
+        let receive = symbol(&analysis, "Proxy", "receive()").mermaid_id("f");
+        let fallback = symbol(&analysis, "Proxy", "fallback()").mermaid_id("f");
+        let delegate = symbol(&analysis, "Proxy", "delegatecall").mermaid_id("d");
+
+        // both of them have their own node
+        assert!(mermaid.contains(&format!("{receive}[receive]:::external_payable\n")));
+        assert!(mermaid.contains(&format!("{fallback}[fallback]:::external_payable\n")));
+        assert!(mermaid.contains(&format!(
+            "{receive} --> {}\n",
+            symbol(&analysis, "Proxy", "received").mermaid_id("s")
+        )));
+        // the proxy forwards the calls to its implementation
+        assert!(mermaid.contains(&format!("{fallback} -->|delegatecall| {delegate}\n")));
+        assert!(mermaid.contains(&format!("{delegate}{{{{implementation}}}}:::delegate\n")));
+    }
+
+    #[test]
+    fn factories_deploy_the_created_contracts() {
+        let analysis = analyze_source(
+            "Factory.sol",
+            r#"
+pragma solidity ^0.8.0;
+library Clones {
+    function clone(address implementation) internal returns (address instance) {
+        assembly { instance := create(0, 0x09, 0x37) }
+    }
+}
+contract Pool {
+    address factory;
+    constructor(address owner) { factory = owner; }
+    function setFactory(address owner) external { factory = owner; }
+}
+contract Factory {
+    address[] pools;
+    address implementation;
+    function deploy() external { pools.push(address(new Pool(msg.sender))); }
+    function deploySalted(bytes32 salt) external {
+        pools.push(address(new Pool{salt: salt}(msg.sender)));
+    }
+    function cloneOne() external { pools.push(Clones.clone(implementation)); }
+}
+"#,
+        );
+        let creation = |signature: &str| {
+            calls(&analysis, "Factory", signature)
+                .into_iter()
+                .filter(|call| matches!(call, Call::Create(..)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            creation("deploy()"),
+            vec![Call::Create(
+                Some(String::from("Pool")),
+                String::from("create")
+            )]
+        );
+        assert_eq!(
+            creation("deploySalted(bytes32)"),
+            vec![Call::Create(
+                Some(String::from("Pool")),
+                String::from("create2")
+            )]
+        );
+        assert_eq!(
+            creation("cloneOne()"),
+            vec![Call::Create(None, String::from("clone"))]
+        );
+
+        let mermaid = poseidon::generate_mermaid(
+            &analysis.contracts,
+            &analysis.interfaces,
+            &analysis.slots,
+            &analysis.namespaces,
+            &PoseidonOptions::default(),
+        );
+        let function = |signature: &str| symbol(&analysis, "Factory", signature).mermaid_id("f");
+        let clone = Symbol::new("", "Created", "clone").mermaid_id("c");
+
+        // charted contracts are deployed into their subgraph
+        assert!(mermaid.contains(&format!("{} -->|deploys| Pool\n", function("deploy()"))));
+        assert!(mermaid.contains(&format!(
+            "{} -->|deploys with create2| Pool\n",
+            function("deploySalted(bytes32)")
+        )));
+        assert!(mermaid.contains(&format!("{clone}[[clone]]:::created\n")));
+        assert!(mermaid.contains(&format!(
+            "{} -->|deploys with clone| {clone}\n",
+            function("cloneOne()")
+        )));
+    }
 }
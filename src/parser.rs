@@ -2,9 +2,11 @@ use crate::structures::*;
 use solang_parser::{
     parse,
     pt::{
+        CatchClause,
         ContractDefinition,
         ContractPart,
         ContractTy,
+        ErrorDefinition,
         Expression as SolangExpression,
         FunctionAttribute,
         FunctionDefinition,
@@ -12,15 +14,22 @@ use solang_parser::{
         Identifier,
         IdentifierPath,
         Mutability,
+        Parameter,
         SourceUnitPart,
         Statement as SolangStatement,
         StorageLocation,
         StructDefinition,
+        Type,
+        Using,
+        UsingList,
         VariableAttribute,
         VariableDefinition,
         Visibility,
+        YulBlock,
         YulExpression,
+        YulFunctionCall,
         YulStatement,
+        YulSwitchOptions,
     },
 };
 use std::collections::HashMap;
@@ -45,6 +54,7 @@ pub enum ParserError {
     EnumValueNotDefined,
 
     IncorrectTypeOfVariable,
+    InheritanceNotLinearizable(String),
 }
 
 impl From<std::io::Error> for ParserError {
@@ -57,39 +67,61 @@ pub struct Parser<'a> {
     members_map: &'a mut HashMap<String, MemberType>,
     modifiers_map: &'a mut HashMap<String, FunctionDefinition>,
     // mapping function => struct return
-    storage_pointers: &'a mut HashMap<String, String>,
+    storage_pointers: &'a mut HashMap<Symbol, Symbol>,
+    // function => position of the storage namespace it returns, e.g. `diamond.standard.storage`
+    storage_namespaces: &'a mut HashMap<Symbol, String>,
     // Contract.Struct => Struct
-    structs: &'a mut HashMap<String, StructDefinition>,
-    // declaration => Contract.Struct
-    local_storage_pointers: &'a mut HashMap<String, String>,
-    // depth => [declaration_0, declaration_1 ...]
-    local_storage_pointers_declared: &'a mut HashMap<u8, Vec<String>>,
+    structs: &'a mut HashMap<Symbol, StructDefinition>,
+    // lexical scopes of the parsed function, the innermost last, declaration => local variable
+    scopes: &'a mut Vec<HashMap<String, LocalVariable>>,
     // Contract.Struct => [field_0, field_1 ...]
-    storage_access: &'a mut HashMap<String, Vec<String>>,
-    current_depth: u8,
+    storage_access: &'a mut HashMap<Symbol, Vec<String>>,
+    // (source unit, Contract) => definition of the contract
+    contract_definitions: &'a mut HashMap<(String, String), ContractDefinition>,
+    // (source unit, Contract) => source unit defining the contract visible there
+    source_units: &'a mut HashMap<(String, String), String>,
+    // function => function declared at the file level
+    free_functions: &'a mut HashMap<String, MemberType>,
+    // Contract or file scope => using directives declared there
+    using_directives: &'a mut HashMap<String, Vec<UsingDirective>>,
+    // Error in the contract or file scope declaring it => declaration of the custom error,
+    // its member is the signature of the error
+    errors: &'a mut HashMap<Symbol, Symbol>,
     current_contract: String,
+    current_source_unit: String,
 }
 
 impl<'a> Parser<'a> {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         members_map: &'a mut HashMap<String, MemberType>,
         modifiers_map: &'a mut HashMap<String, FunctionDefinition>,
-        storage_pointers: &'a mut HashMap<String, String>,
-        structs: &'a mut HashMap<String, StructDefinition>,
-        local_variables: &'a mut HashMap<String, String>,
-        local_variables_declared: &'a mut HashMap<u8, Vec<String>>,
-        storage_access: &'a mut HashMap<String, Vec<String>>,
+        storage_pointers: &'a mut HashMap<Symbol, Symbol>,
+        storage_namespaces: &'a mut HashMap<Symbol, String>,
+        structs: &'a mut HashMap<Symbol, StructDefinition>,
+        scopes: &'a mut Vec<HashMap<String, LocalVariable>>,
+        storage_access: &'a mut HashMap<Symbol, Vec<String>>,
+        contract_definitions: &'a mut HashMap<(String, String), ContractDefinition>,
+        source_units: &'a mut HashMap<(String, String), String>,
+        free_functions: &'a mut HashMap<String, MemberType>,
+        using_directives: &'a mut HashMap<String, Vec<UsingDirective>>,
+        errors: &'a mut HashMap<Symbol, Symbol>,
     ) -> Self {
         Parser {
             members_map,
             modifiers_map,
             storage_pointers,
+            storage_namespaces,
             structs,
-            local_storage_pointers: local_variables,
-            local_storage_pointers_declared: local_variables_declared,
+            scopes,
             storage_access,
-            current_depth: 0,
+            contract_definitions,
+            source_units,
+            free_functions,
+            using_directives,
+            errors,
             current_contract: String::new(),
+            current_source_unit: String::new(),
         }
     }
 
@@ -100,8 +132,15 @@ impl<'a> Parser<'a> {
 
     /// Parses a fil and returns the vec of ParserOutput or a ParserError
     ///
+    /// `path` the path to the solidity file
     /// `content` the content of a solidity file
-    pub fn parse_file(&mut self, content: &str) -> Result<Vec<ParserOutput>, ParserError> {
+    pub fn parse_file(
+        &mut self,
+        path: &str,
+        content: &str,
+    ) -> Result<Vec<ParserOutput>, ParserError> {
+        self.current_source_unit = path.to_string();
+
         let token_tree = parse(content, 0).map_err(|errors| {
             ParserError::FileCorrupted(errors.iter().map(|error| error.message.clone()).collect())
         })?;
@@ -109,7 +148,32 @@ impl<'a> Parser<'a> {
         let mut output = Vec::new();
         let source_unit = token_tree.0;
 
+        // functions declared at the file level may be called from any contract
+        for (name, member) in self.free_functions.clone() {
+            self.members_map.insert(name, member);
+        }
+
+        let free_functions = source_unit
+            .0
+            .iter()
+            .filter_map(|source_unit_part| {
+                match source_unit_part {
+                    SourceUnitPart::FunctionDefinition(function_definition) => {
+                        Some(*function_definition.clone())
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if !free_functions.is_empty() {
+            output.push(self.parse_file_scope(path, &free_functions)?);
+        }
+
         for source_unit_part in source_unit.0.iter() {
+            // items declared at the file level are not contracts: constants only resolve storage
+            // positions in `extract_storage_pointers`, errors are registered by
+            // `extract_all_structs` and enums do not change the state
             if let SourceUnitPart::ContractDefinition(contract) = source_unit_part {
                 output.push(self.handle_contract_definition(contract)?);
             }
@@ -118,6 +182,53 @@ impl<'a> Parser<'a> {
         Ok(output)
     }
 
+    /// Parses the functions declared at the file level as a library named after the file
+    ///
+    /// `path` the path to the solidity file
+    /// `function_definitions` the Solang definitions of the free functions
+    fn parse_file_scope(
+        &mut self,
+        path: &str,
+        function_definitions: &[FunctionDefinition],
+    ) -> Result<ParserOutput, ParserError> {
+        let name = Self::file_scope(path);
+
+        self.current_contract = name.clone();
+
+        let functions = function_definitions
+            .iter()
+            .map(|function_definition| self.parse_function(function_definition))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let slots = self
+            .storage_access
+            .iter()
+            .map(|entry| StorageSlot::new(entry.0.clone(), entry.1.clone()))
+            .collect();
+
+        Ok(ParserOutput::Library(
+            name.clone(),
+            Contract {
+                name,
+                source_unit: path.to_string(),
+                functions,
+                slots,
+                ..Default::default()
+            },
+        ))
+    }
+
+    /// Returns the name of the scope of the items declared at the file level, e.g.
+    /// `/project/src/Storage.sol`
+    ///
+    /// Files of different directories may share their names, so the scope is named by the
+    /// normalized path of the file
+    ///
+    /// `path` the path to the solidity file
+    fn file_scope(path: &str) -> String {
+        path.to_string()
+    }
+
     /// Parses a contract definition and returns a ParserOutput
     ///
     /// `contract_definition` the Solang contract definition
@@ -149,70 +260,500 @@ impl<'a> Parser<'a> {
         }
     }
 
-    pub fn extract_storage_pointers(&mut self, content: &str) -> Result<(), ParserError> {
+    /// Returns the paths imported by a file, as written in the import directives
+    ///
+    /// `content` the content of the file
+    pub fn extract_imports(&self, content: &str) -> Result<Vec<String>, ParserError> {
+        let token_tree = parse(content, 0).map_err(|errors| {
+            ParserError::FileCorrupted(errors.iter().map(|error| error.message.clone()).collect())
+        })?;
+
+        let source_unit = token_tree.0;
+
+        Ok(source_unit
+            .0
+            .iter()
+            .filter_map(|source_unit_part| {
+                match source_unit_part {
+                    SourceUnitPart::ImportDirective(import) => {
+                        Some(import.literal().string.clone())
+                    }
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+
+    pub fn extract_storage_pointers(
+        &mut self,
+        path: &str,
+        content: &str,
+    ) -> Result<(), ParserError> {
         let token_tree = parse(content, 0).map_err(|errors| {
             ParserError::FileCorrupted(errors.iter().map(|error| error.message.clone()).collect())
         })?;
 
         let source_unit = token_tree.0;
 
+        // storage positions are usually constants of the library or of the file
+        let mut file_constants = HashMap::new();
         for source_unit_part in source_unit.0.iter() {
-            if let SourceUnitPart::ContractDefinition(contract_definition) = source_unit_part {
-                let contract_name = self.parse_identifier(&contract_definition.name.clone());
+            if let SourceUnitPart::VariableDefinition(variable_definition) = source_unit_part {
+                Self::collect_constant(variable_definition, &mut file_constants);
+            }
+        }
 
-                for part in contract_definition.parts.iter() {
-                    if let ContractPart::FunctionDefinition(function_definition) = part {
-                        // if function returns a storage pointer
-                        let return_param = function_definition
-                            .returns
-                            .iter()
-                            .filter_map(|tuple| tuple.1.clone())
-                            .filter(|param| param.storage.is_some())
-                            .find(|param| {
-                                matches!(
-                                    param.storage.clone().unwrap(),
-                                    StorageLocation::Storage(_)
-                                )
-                            });
-                        if let Some(return_param) = return_param {
-                            let function_header = self.parse_function_header(function_definition);
+        for source_unit_part in source_unit.0.iter() {
+            match source_unit_part {
+                SourceUnitPart::ContractDefinition(contract_definition) => {
+                    let contract_name = self.parse_identifier(&contract_definition.name.clone());
+
+                    let mut constants = file_constants.clone();
+                    for part in contract_definition.parts.iter() {
+                        if let ContractPart::VariableDefinition(variable_definition) = part {
+                            Self::collect_constant(variable_definition, &mut constants);
+                        }
+                    }
 
-                            if let SolangExpression::Variable(ident) = return_param.ty {
-                                let parsed_ident = self.parse_identifier(&Some(ident));
-                                self.storage_pointers.insert(
-                                    format!("{contract_name}_{}", function_header.name),
-                                    format!("{contract_name}_{parsed_ident}"),
-                                );
-                            }
+                    for part in contract_definition.parts.iter() {
+                        if let ContractPart::FunctionDefinition(function_definition) = part {
+                            self.extract_storage_pointer(
+                                path,
+                                &contract_name,
+                                function_definition,
+                                &constants,
+                            );
                         }
                     }
                 }
+                SourceUnitPart::FunctionDefinition(function_definition) => {
+                    self.extract_storage_pointer(
+                        path,
+                        &Self::file_scope(path),
+                        function_definition,
+                        &file_constants,
+                    );
+                }
+                _ => (),
             }
         }
         Ok(())
     }
 
-    pub fn extract_all_structs(&mut self, content: &str) -> Result<(), ParserError> {
+    /// Registers the contracts visible in a file through its imports
+    ///
+    /// The contracts of the imported files are visible, as well as the ones they import,
+    /// the closest definition of a name is kept
+    ///
+    /// `path` the path to the solidity file
+    /// `imports` file => files it imports, for all the parsed files
+    pub fn register_imports(&mut self, path: &str, imports: &HashMap<String, Vec<String>>) {
+        let mut visited = vec![path.to_string()];
+        let mut index = 0;
+
+        while index < visited.len() {
+            let source_unit = visited[index].clone();
+
+            for (defining, name) in self.contract_definitions.keys() {
+                if *defining == source_unit {
+                    self.source_units
+                        .entry((path.to_string(), name.clone()))
+                        .or_insert_with(|| source_unit.clone());
+                }
+            }
+
+            for imported in imports.get(&source_unit).into_iter().flatten() {
+                if !visited.contains(imported) {
+                    visited.push(imported.clone());
+                }
+            }
+
+            index += 1;
+        }
+    }
+
+    /// Computes the C3 linearization of a contract or an interface
+    ///
+    /// `contract` the source unit defining the contract and the name of the contract
+    ///
+    /// Returns the linearized contracts, the contract itself first and the most base one last
+    pub fn linearize(
+        &self,
+        contract: &(String, String),
+    ) -> Result<Vec<(String, String)>, ParserError> {
+        self.linearize_visiting(contract, &mut Vec::default())
+    }
+
+    /// Computes the C3 linearization of a contract
+    ///
+    /// `contract` the source unit defining the contract and the name of the contract
+    /// `visiting` the contracts whose linearization is being computed, used to detect cycles
+    fn linearize_visiting(
+        &self,
+        contract: &(String, String),
+        visiting: &mut Vec<(String, String)>,
+    ) -> Result<Vec<(String, String)>, ParserError> {
+        let (source_unit, name) = contract;
+        if visiting.contains(contract) {
+            return Err(ParserError::InheritanceNotLinearizable(name.to_string()))
+        }
+        visiting.push(contract.clone());
+
+        // Solidity lists the bases from the most base one to the most derived one,
+        // the bases are the ones visible in the source unit defining the contract
+        let bases = self
+            .contract_definitions
+            .get(contract)
+            .map(|contract_definition| {
+                contract_definition
+                    .base
+                    .iter()
+                    .rev()
+                    .map(|base| self.qualify(source_unit, &self.parse_identifier_path(&base.name)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut sequences = bases
+            .iter()
+            .map(|base| self.linearize_visiting(base, visiting))
+            .collect::<Result<Vec<_>, _>>()?;
+        sequences.push(bases);
+
+        let mut linearization = vec![contract.clone()];
+
+        loop {
+            sequences.retain(|sequence| !sequence.is_empty());
+
+            if sequences.is_empty() {
+                break
+            }
+
+            // the next one is the first head which does not appear in the tail of any sequence
+            let head = sequences
+                .iter()
+                .map(|sequence| &sequence[0])
+                .find(|candidate| {
+                    !sequences
+                        .iter()
+                        .any(|sequence| sequence[1..].contains(candidate))
+                })
+                .cloned()
+                .ok_or_else(|| ParserError::InheritanceNotLinearizable(name.to_string()))?;
+
+            for sequence in sequences.iter_mut() {
+                if sequence[0] == head {
+                    sequence.remove(0);
+                }
+            }
+
+            linearization.push(head);
+        }
+
+        visiting.pop();
+
+        Ok(linearization)
+    }
+
+    /// Returns the positions of the storage namespaces, e.g. `diamond.standard.storage`
+    pub fn storage_namespaces(&self) -> Vec<String> {
+        let mut namespaces = self
+            .storage_namespaces
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        namespaces.sort();
+        namespaces.dedup();
+        namespaces
+    }
+
+    /// Saves the initial value of a constant
+    ///
+    /// `variable_definition` the Solang variable definition
+    /// `constants` the constants, name => initial value
+    fn collect_constant(
+        variable_definition: &VariableDefinition,
+        constants: &mut HashMap<String, SolangExpression>,
+    ) {
+        if !variable_definition
+            .attrs
+            .iter()
+            .any(|attribute| matches!(attribute, VariableAttribute::Constant(_)))
+        {
+            return
+        }
+        if let (Some(name), Some(initializer)) =
+            (&variable_definition.name, &variable_definition.initializer)
+        {
+            constants.insert(name.name.clone(), initializer.clone());
+        }
+    }
+
+    /// Saves the struct returned by a function if it returns a storage pointer
+    ///
+    /// The function returns a storage namespace if it moves the returned pointer to a position,
+    /// e.g. `ds.slot := position` where `position = keccak256("diamond.standard.storage")`
+    ///
+    /// `path` the path to the solidity file
+    /// `scope` the contract or the file scope defining the function
+    /// `function_definition` the Solang function definition
+    /// `constants` the constants visible in the function, name => initial value
+    fn extract_storage_pointer(
+        &mut self,
+        path: &str,
+        scope: &str,
+        function_definition: &FunctionDefinition,
+        constants: &HashMap<String, SolangExpression>,
+    ) {
+        self.current_source_unit = path.to_string();
+        self.current_contract = scope.to_string();
+
+        // if function returns a storage pointer
+        let return_param = function_definition
+            .returns
+            .iter()
+            .filter_map(|tuple| tuple.1.clone())
+            .filter(|param| param.storage.is_some())
+            .find(|param| matches!(param.storage.clone().unwrap(), StorageLocation::Storage(_)));
+        if let Some(return_param) = return_param {
+            let function_header = self.parse_function_header(function_definition);
+
+            let function_symbol = Symbol::new(path, scope, &function_header.signature());
+
+            if let SolangExpression::Variable(ident) = return_param.ty {
+                let parsed_ident = self.parse_identifier(&Some(ident));
+                let struct_symbol = self.resolve_struct(&parsed_ident);
+                self.storage_pointers
+                    .insert(function_symbol.clone(), struct_symbol);
+            }
+
+            let (Some(pointer), Some(body)) = (&return_param.name, &function_definition.body)
+            else {
+                return
+            };
+
+            // local variables may hold the position as well
+            let mut constants = constants.clone();
+            Self::collect_local_values(body, &mut constants);
+
+            if let Some(position) = Self::find_slot_assignment(body, &pointer.name)
+                .and_then(|slot| Self::parse_yul_position(&slot, &constants))
+            {
+                self.storage_namespaces.insert(function_symbol, position);
+            }
+        }
+    }
+
+    /// Saves the initial values of the local variables declared in a statement
+    ///
+    /// `statement` the Solang statement
+    /// `values` the values, name => initial value
+    fn collect_local_values(
+        statement: &SolangStatement,
+        values: &mut HashMap<String, SolangExpression>,
+    ) {
+        match statement {
+            SolangStatement::Block { statements, .. } => {
+                for statement in statements.iter() {
+                    Self::collect_local_values(statement, values);
+                }
+            }
+            SolangStatement::VariableDefinition(_, definition, Some(initial_value)) => {
+                if let Some(name) = &definition.name {
+                    values.insert(name.name.clone(), initial_value.clone());
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Returns the position assigned to the slot of a storage pointer in assembly,
+    /// e.g. `position` of `ds.slot := position`
+    ///
+    /// `statement` the Solang statement
+    /// `pointer` the name of the storage pointer
+    fn find_slot_assignment(statement: &SolangStatement, pointer: &str) -> Option<YulExpression> {
+        fn find_in_yul(yul_statement: &YulStatement, pointer: &str) -> Option<YulExpression> {
+            match yul_statement {
+                YulStatement::Assign(_, assigned, value) => {
+                    match assigned.as_slice() {
+                        [YulExpression::SuffixAccess(_, variable, suffix)]
+                            if suffix.name == "slot"
+                                && matches!(variable.as_ref(), YulExpression::Variable(identifier) if identifier.name == pointer) =>
+                        {
+                            Some(value.clone())
+                        }
+                        _ => None,
+                    }
+                }
+                YulStatement::Block(yul_block) | YulStatement::If(_, _, yul_block) => {
+                    yul_block
+                        .statements
+                        .iter()
+                        .find_map(|yul_statement| find_in_yul(yul_statement, pointer))
+                }
+                _ => None,
+            }
+        }
+
+        match statement {
+            SolangStatement::Block { statements, .. } => {
+                statements
+                    .iter()
+                    .find_map(|statement| Self::find_slot_assignment(statement, pointer))
+            }
+            SolangStatement::Assembly { block, .. } => {
+                block
+                    .statements
+                    .iter()
+                    .find_map(|yul_statement| find_in_yul(yul_statement, pointer))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the storage namespace at the position assigned in assembly,
+    /// the hashed string for `keccak256("...")` or the number of a fixed slot
+    ///
+    /// `yul_expression` the assigned position
+    /// `values` the constants and local variables, name => initial value
+    fn parse_yul_position(
+        yul_expression: &YulExpression,
+        values: &HashMap<String, SolangExpression>,
+    ) -> Option<String> {
+        match yul_expression {
+            YulExpression::Variable(identifier) => {
+                Self::parse_position(values.get(&identifier.name)?, values)
+            }
+            YulExpression::NumberLiteral(_, number, ..)
+            | YulExpression::HexNumberLiteral(_, number, _) => Some(format!("slot {number}")),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the storage namespace at the position computed by the expression
+    ///
+    /// ERC-7201 positions, e.g. `keccak256(abi.encode(uint256(keccak256("x")) - 1))`, are named
+    /// after the innermost hashed string
+    ///
+    /// `expression` the computation of the position
+    /// `values` the constants and local variables, name => initial value
+    fn parse_position(
+        expression: &SolangExpression,
+        values: &HashMap<String, SolangExpression>,
+    ) -> Option<String> {
+        match expression {
+            SolangExpression::FunctionCall(_, function, args) => {
+                if let (
+                    SolangExpression::Variable(identifier),
+                    [SolangExpression::StringLiteral(literals)],
+                ) = (function.as_ref(), args.as_slice())
+                {
+                    if identifier.name == "keccak256" {
+                        return Some(
+                            literals
+                                .iter()
+                                .map(|literal| literal.string.clone())
+                                .collect::<String>(),
+                        )
+                    }
+                }
+                args.iter()
+                    .find_map(|arg| Self::parse_position(arg, values))
+            }
+            SolangExpression::Variable(identifier) => {
+                match values.get(&identifier.name)? {
+                    // a local variable may be named after the constant it is initialized with
+                    SolangExpression::Variable(value) if value.name == identifier.name => None,
+                    value => Self::parse_position(value, values),
+                }
+            }
+            SolangExpression::Parenthesis(_, expression)
+            | SolangExpression::BitwiseNot(_, expression) => {
+                Self::parse_position(expression, values)
+            }
+            SolangExpression::Add(_, left, right)
+            | SolangExpression::Subtract(_, left, right)
+            | SolangExpression::BitwiseAnd(_, left, right)
+            | SolangExpression::BitwiseOr(_, left, right)
+            | SolangExpression::BitwiseXor(_, left, right) => {
+                Self::parse_position(left, values).or_else(|| Self::parse_position(right, values))
+            }
+            SolangExpression::NumberLiteral(_, number, ..)
+            | SolangExpression::HexNumberLiteral(_, number, _) => Some(format!("slot {number}")),
+            _ => None,
+        }
+    }
+
+    pub fn extract_all_structs(&mut self, path: &str, content: &str) -> Result<(), ParserError> {
         let token_tree = parse(content, 0).map_err(|errors| {
             ParserError::FileCorrupted(errors.iter().map(|error| error.message.clone()).collect())
         })?;
 
         let source_unit = token_tree.0;
 
+        let scope = Self::file_scope(path);
+
         for source_unit_part in source_unit.0.iter() {
+            match source_unit_part {
+                // structs and functions may be declared at the file level too
+                SourceUnitPart::StructDefinition(struct_definition) => {
+                    let name = self.parse_identifier(&struct_definition.name);
+                    self.structs
+                        .insert(Symbol::new(path, &scope, &name), *struct_definition.clone());
+                }
+                SourceUnitPart::Using(using) => {
+                    let using_directive = self.parse_using(using);
+                    self.using_directives
+                        .entry(scope.clone())
+                        .or_default()
+                        .push(using_directive);
+                }
+                SourceUnitPart::FunctionDefinition(function_definition) => {
+                    let function_header = self.parse_function_header(function_definition);
+                    let symbol = Symbol::new(path, &scope, &function_header.signature());
+                    Self::register_function(&mut *self.free_functions, function_header, symbol);
+                }
+                SourceUnitPart::ErrorDefinition(error_definition) => {
+                    self.register_error(path, &scope, error_definition);
+                }
+                _ => (),
+            }
+
             if let SourceUnitPart::ContractDefinition(contract_definition) = source_unit_part {
                 let contract_name = self.parse_identifier(&contract_definition.name);
 
+                // contracts and interfaces may be used as types of variables or inherited
+                self.contract_definitions.insert(
+                    (path.to_string(), contract_name.clone()),
+                    *contract_definition.clone(),
+                );
+                self.source_units
+                    .insert((path.to_string(), contract_name.clone()), path.to_string());
+
                 // first we need to know functions that exist
                 for part in contract_definition.parts.iter() {
-                    if let ContractPart::StructDefinition(struct_definition) = part {
-                        // we will save struct definitions as structs might be used as storage containers
-                        let name = self.parse_identifier(&struct_definition.name);
-                        // panic if unnamed struct
-                        self.structs.insert(
-                            format!("{}_{}", contract_name, name.clone()),
-                            *struct_definition.clone(),
-                        );
+                    match part {
+                        ContractPart::StructDefinition(struct_definition) => {
+                            // we will save struct definitions as structs might be used as storage containers
+                            let name = self.parse_identifier(&struct_definition.name);
+                            // panic if unnamed struct
+                            self.structs.insert(
+                                Symbol::new(path, &contract_name, &name),
+                                *struct_definition.clone(),
+                            );
+                        }
+                        ContractPart::Using(using) => {
+                            let using_directive = self.parse_using(using);
+                            self.using_directives
+                                .entry(contract_name.clone())
+                                .or_default()
+                                .push(using_directive);
+                        }
+                        ContractPart::ErrorDefinition(error_definition) => {
+                            self.register_error(path, &contract_name, error_definition);
+                        }
+                        _ => (),
                     }
                 }
             }
@@ -221,6 +762,115 @@ impl<'a> Parser<'a> {
         Ok(())
     }
 
+    /// Saves a custom error, errors are identified by their names in the contract or the file
+    /// scope declaring them
+    ///
+    /// `path` the path to the solidity file
+    /// `scope` the contract or the file scope declaring the error
+    /// `error_definition` the Solang error definition
+    fn register_error(&mut self, path: &str, scope: &str, error_definition: &ErrorDefinition) {
+        let name = self.parse_identifier(&error_definition.name);
+        let params = error_definition
+            .fields
+            .iter()
+            .map(|field| self.parse_type(&field.ty))
+            .collect::<Vec<_>>();
+
+        self.errors.insert(
+            Symbol::new(path, scope, &name),
+            Symbol::new(path, scope, &format!("{name}({})", params.join(","))),
+        );
+    }
+
+    /// Returns the declaration of the custom error with the name
+    ///
+    /// Errors which were not declared in the analyzed files are attributed to the current
+    /// contract, their parameters are known only if no arguments are passed
+    ///
+    /// `qualifier` the contract the error is accessed through, e.g. `IErrors` of
+    /// `IErrors.Unauthorized`
+    /// `name` the name of the error
+    /// `has_args` arguments are passed to the error
+    fn resolve_error(&self, qualifier: Option<&str>, name: &str, has_args: bool) -> Symbol {
+        self.find_error(qualifier, name).unwrap_or_else(|| {
+            let member = if has_args {
+                name.to_string()
+            } else {
+                format!("{name}()")
+            };
+            self.symbol(&self.current_contract, &member)
+        })
+    }
+
+    /// Returns the declaration of the custom error with the name if it was declared in the
+    /// analyzed files
+    ///
+    /// Errors are looked up in the contract and its bases, the most derived first, then in the
+    /// file scope of the current file and in the file scopes of the other files
+    ///
+    /// `qualifier` the contract the error is accessed through, if any
+    /// `name` the name of the error
+    fn find_error(&self, qualifier: Option<&str>, name: &str) -> Option<Symbol> {
+        let contract = qualifier.unwrap_or(&self.current_contract);
+        let file_scope = Symbol::new(
+            &self.current_source_unit,
+            &Self::file_scope(&self.current_source_unit),
+            name,
+        );
+
+        self.linearize_visible(contract)
+            .iter()
+            .map(|(source_unit, contract)| Symbol::new(source_unit, contract, name))
+            .chain(qualifier.is_none().then_some(file_scope))
+            .find_map(|scope| self.errors.get(&scope).cloned())
+            .or_else(|| {
+                // errors declared at the file level of the imported files
+                self.errors
+                    .iter()
+                    .filter(|(scope, _)| {
+                        qualifier.is_none()
+                            && scope.member == name
+                            && scope.contract == Self::file_scope(&scope.source_unit)
+                    })
+                    .min_by_key(|(scope, _)| scope.source_unit.clone())
+                    .map(|(_, error)| error.clone())
+            })
+    }
+
+    /// Returns the custom error of a `revert` statement, e.g. `IErrors.Unauthorized`
+    ///
+    /// `error` the path of the error
+    /// `has_args` arguments are passed to the error
+    fn parse_revert_error(&self, error: &IdentifierPath, has_args: bool) -> Option<Symbol> {
+        let (name, qualifier) = error.identifiers.split_last()?;
+
+        Some(self.resolve_error(
+            qualifier.last().map(|qualifier| qualifier.name.as_str()),
+            &name.name,
+            has_args,
+        ))
+    }
+
+    /// Returns the custom error constructed by the expression, e.g. `Unauthorized(caller)`
+    /// or `IErrors.Unauthorized(caller)`
+    ///
+    /// `expression` the expression passed to `require`
+    fn parse_error_call(&self, expression: &SolangExpression) -> Option<Symbol> {
+        let SolangExpression::FunctionCall(_, function, _) = expression else {
+            return None
+        };
+        match function.as_ref() {
+            SolangExpression::Variable(identifier) => self.find_error(None, &identifier.name),
+            SolangExpression::MemberAccess(_, qualifier, identifier) => {
+                let SolangExpression::Variable(qualifier) = qualifier.as_ref() else {
+                    return None
+                };
+                self.find_error(Some(&qualifier.name), &identifier.name)
+            }
+            _ => None,
+        }
+    }
+
     /// Parses a contract
     ///
     /// `contract_definition` the Solang contract definition
@@ -242,66 +892,60 @@ impl<'a> Parser<'a> {
             .collect();
 
         let mut fields: Vec<ContractField> = Default::default();
+        let mut immutables: Vec<ContractField> = Default::default();
+        // storage and immutables initialized by their declaration on deployment
+        let mut initializations: Vec<Call> = Default::default();
+        let mut events: Vec<Event> = Default::default();
         let mut functions: Vec<Function> = Default::default();
         let mut constructor: Function = Default::default();
         let mut modifiers: Vec<Function> = Default::default();
 
         // first we need to know functions and storage fields that exist
-        for part in contract_definition.parts.iter() {
-            match part {
-                ContractPart::VariableDefinition(variable_definition) => {
-                    let name = self.parse_identifier(&variable_definition.name);
-                    if variable_definition.attrs.iter().any(|item| {
-                        matches!(
-                            item,
-                            VariableAttribute::Constant(_) | VariableAttribute::Immutable(_)
-                        )
-                    }) {
-                        // we do not care about consants and immutables as they do not change state of the contract so we skip
-                        // @todo we do care about them as they could hide the type of contract we want to call later
-                        continue
-                    }
-                    self.members_map.insert(
-                        name.clone(),
-                        MemberType::StorageField(contract_name.clone()),
-                    );
-                }
-                ContractPart::FunctionDefinition(function_definition) => {
-                    // @todo function might take storage as input param
-                    let fn_name = self.parse_identifier(&function_definition.name);
-                    match function_definition.ty {
-                        FunctionTy::Function => {
-                            let function_header = self.parse_function_header(function_definition);
-                            self.members_map.insert(
-                                fn_name.clone(),
-                                MemberType::Function(function_header, contract_name.clone()),
-                            );
-                        }
-                        FunctionTy::Modifier => {
-                            self.modifiers_map
-                                .insert(fn_name.clone(), *function_definition.clone());
-                        }
-                        _ => (),
-                    }
-                }
-                _ => (),
+        // members of the bases are accessible too, the more derived ones override them
+        let linearization =
+            self.linearize(&(self.current_source_unit.clone(), contract_name.clone()))?;
+        for base in linearization.iter().skip(1).rev() {
+            if let Some(base_definition) = self.contract_definitions.get(base).cloned() {
+                self.register_members(&base.0, &base_definition);
             }
         }
+        self.register_members(&self.current_source_unit.clone(), contract_definition);
 
         for part in contract_definition.parts.iter() {
             match part {
                 ContractPart::VariableDefinition(variable_definition) => {
-                    if variable_definition.attrs.iter().any(|item| {
-                        matches!(
-                            item,
-                            VariableAttribute::Constant(_) | VariableAttribute::Immutable(_)
-                        )
-                    }) {
-                        // we do not care about consants and immutables as they do not change state of the contract so we skip
+                    if variable_definition
+                        .attrs
+                        .iter()
+                        .any(|item| matches!(item, VariableAttribute::Constant(_)))
+                    {
+                        // we do not care about consants as they do not change state of the contract so we skip
                         continue
                     }
                     let parsed_field = self.parse_storage_field(variable_definition)?;
-                    fields.push(parsed_field);
+                    let call_type = if variable_definition
+                        .attrs
+                        .iter()
+                        .any(|item| matches!(item, VariableAttribute::Immutable(_)))
+                    {
+                        immutables.push(parsed_field.clone());
+                        CallType::CallingImmutable
+                    } else {
+                        fields.push(parsed_field.clone());
+                        CallType::CallingStorage
+                    };
+
+                    if let Some(initializer) = &variable_definition.initializer {
+                        initializations.extend(self.parse_expression(initializer));
+                        initializations.push(Call::WriteStorage(
+                            call_type,
+                            self.symbol(&contract_name, &parsed_field.name),
+                        ));
+                    }
+                }
+                ContractPart::EventDefinition(event_definition) => {
+                    let name = self.parse_identifier(&event_definition.name);
+                    events.push(Event { name });
                 }
                 ContractPart::FunctionDefinition(function_definition) => {
                     let parsed_function = self.parse_function(function_definition)?;
@@ -315,6 +959,11 @@ impl<'a> Parser<'a> {
             }
         }
 
+        // declarations are initialized before the body of the constructor is executed
+        constructor.header.name = contract_name.clone();
+        initializations.append(&mut constructor.calls);
+        constructor.calls = initializations;
+
         let slots = self
             .storage_access
             .iter()
@@ -323,16 +972,118 @@ impl<'a> Parser<'a> {
 
         Ok(Contract {
             name: contract_name,
+            source_unit: self.current_source_unit.clone(),
             fields,
+            immutables,
             slots,
+            events,
             functions,
             constructor,
+            constructors: Vec::default(),
             modifiers,
             base,
+            linearization: Vec::default(),
             is_abstract: matches!(contract_definition.ty, ContractTy::Abstract(_)),
         })
     }
 
+    /// Saves the storage fields and functions of a contract to the members map
+    ///
+    /// `source_unit` the path of the file defining the contract
+    /// `contract_definition` the Solang contract definition
+    fn register_members(&mut self, source_unit: &str, contract_definition: &ContractDefinition) {
+        let contract_name = self.parse_identifier(&contract_definition.name);
+
+        for part in contract_definition.parts.iter() {
+            match part {
+                ContractPart::VariableDefinition(variable_definition) => {
+                    let name = self.parse_identifier(&variable_definition.name);
+                    if variable_definition
+                        .attrs
+                        .iter()
+                        .any(|item| matches!(item, VariableAttribute::Constant(_)))
+                    {
+                        // we do not care about consants as they do not change state of the contract so we skip
+                        // @todo we do care about them as they could hide the type of contract we want to call later
+                        continue
+                    }
+                    if variable_definition
+                        .attrs
+                        .iter()
+                        .any(|item| matches!(item, VariableAttribute::Immutable(_)))
+                    {
+                        // immutables are only set on deployment
+                        self.members_map.insert(
+                            name.clone(),
+                            MemberType::Immutable(Symbol::new(source_unit, &contract_name, &name)),
+                        );
+                        continue
+                    }
+                    let variable_type = self.parse_type(&variable_definition.ty);
+                    self.members_map.insert(
+                        name.clone(),
+                        MemberType::StorageField(
+                            Symbol::new(source_unit, &contract_name, &name),
+                            variable_type,
+                        ),
+                    );
+                }
+                ContractPart::FunctionDefinition(function_definition) => {
+                    // @todo function might take storage as input param
+                    let fn_name = self.parse_identifier(&function_definition.name);
+                    match function_definition.ty {
+                        FunctionTy::Function => {
+                            let function_header = self.parse_function_header(function_definition);
+                            let symbol = Symbol::new(
+                                source_unit,
+                                &contract_name,
+                                &function_header.signature(),
+                            );
+                            Self::register_function(
+                                &mut *self.members_map,
+                                function_header,
+                                symbol,
+                            );
+                        }
+                        FunctionTy::Modifier => {
+                            self.modifiers_map
+                                .insert(fn_name.clone(), *function_definition.clone());
+                        }
+                        _ => (),
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Saves an overload of a function to the members, the overload with the same signature
+    /// is overridden
+    ///
+    /// `members` the members map
+    /// `function_header` the header of the function
+    /// `symbol` the function
+    fn register_function(
+        members: &mut HashMap<String, MemberType>,
+        function_header: FunctionHeader,
+        symbol: Symbol,
+    ) {
+        let signature = function_header.signature();
+
+        match members.get_mut(&function_header.name) {
+            Some(MemberType::Function(overloads)) => {
+                overloads.retain(|(known, _)| known.signature() != signature);
+                overloads.push((function_header, symbol));
+            }
+            _ => {
+                members.insert(
+                    function_header.name.clone(),
+                    MemberType::Function(vec![(function_header, symbol)]),
+                );
+            }
+        }
+    }
+
     /// Parses an interface
     ///
     /// `contract_definition` the Solang interface definition
@@ -345,6 +1096,12 @@ impl<'a> Parser<'a> {
     ) -> Result<Interface, ParserError> {
         let name = self.parse_identifier(&contract_definition.name);
 
+        let base = contract_definition
+            .base
+            .iter()
+            .map(|base| self.parse_identifier_path(&base.name))
+            .collect();
+
         let mut function_headers: Vec<FunctionHeader> = Default::default();
 
         for part in contract_definition.parts.iter() {
@@ -358,6 +1115,8 @@ impl<'a> Parser<'a> {
 
         Ok(Interface {
             name,
+            source_unit: self.current_source_unit.clone(),
+            base,
             function_headers,
         })
     }
@@ -386,52 +1145,30 @@ impl<'a> Parser<'a> {
         &mut self,
         function_definition: &FunctionDefinition,
     ) -> Result<Function, ParserError> {
-        let header = self.parse_function_header(function_definition);
-
-        // function can have storage parameters, we will save them to stack
-        let storage_params_declared = function_definition
-            .params
+        // parameters and return variables are declared in the outermost scope of the function
+        self.scopes.push(HashMap::new());
+        for param in function_definition
+            .returns
             .iter()
-            .filter_map(|tuple| tuple.1.clone())
-            .filter(|param| param.storage.is_some() && param.name.is_some())
-            .filter(|param| matches!(param.storage.clone().unwrap(), StorageLocation::Storage(_)))
-            .filter_map(|param| {
-                // map to Option<(param_name, param_type)>
-                let raw_expression = param.ty;
-                let param_type = match raw_expression {
-                    SolangExpression::MemberAccess(_, left, right) => {
-                        // left should be a variable
-                        // @todo handle cases when it is member access etc...
-                        let parsed_right = self.parse_identifier(&Some(right));
-                        if let SolangExpression::Variable(identifier) = *left.clone() {
-                            let parsed_identifier = self.parse_identifier(&Some(identifier));
-                            format!("{parsed_identifier}_{parsed_right}")
-                        } else {
-                            return None
-                        }
-                    }
-                    SolangExpression::Variable(identifier) => {
-                        let parsed_identifier = self.parse_identifier(&Some(identifier));
-                        format!("{}_{parsed_identifier}", self.current_contract)
-                    }
-                    _ => return None,
-                };
-                let parsed_param_name = self.parse_identifier(&param.name);
-
-                Some((parsed_param_name, param_type))
-            })
-            .collect::<Vec<_>>();
-
-        let local_storage_pointers_declared = storage_params_declared
+            .filter_map(|tuple| tuple.1.as_ref())
+        {
+            self.declare_parameter(param);
+        }
+        for param in function_definition
+            .params
             .iter()
-            .map(|(param_name, _)| param_name.clone())
-            .collect();
-
-        for (param_name, param_type) in storage_params_declared {
-            self.local_storage_pointers.insert(param_name, param_type);
+            .filter_map(|tuple| tuple.1.as_ref())
+        {
+            self.declare_parameter(param);
+            if let (Some(name), Some(scope)) = (&param.name, self.scopes.last_mut()) {
+                if let Some(local) = scope.get_mut(&name.name) {
+                    local.parameter = true;
+                }
+            }
         }
-        self.local_storage_pointers_declared
-            .insert(0, local_storage_pointers_declared);
+
+        // arguments of the modifiers may be parameters of the function
+        let header = self.parse_function_header(function_definition);
 
         let calls = if let Some(statement) = &function_definition.body {
             self.parse_statement(statement)?
@@ -439,12 +1176,283 @@ impl<'a> Parser<'a> {
             Vec::default()
         };
 
-        self.local_storage_pointers.clear();
-        self.local_storage_pointers_declared.clear();
+        self.scopes.clear();
 
         Ok(Function { header, calls })
     }
 
+    /// Declares a local variable in the innermost scope, it shadows the members and the
+    /// variables of the outer scopes with the same name
+    ///
+    /// `name` the name of the variable
+    /// `local_variable` the declared variable
+    fn declare_local(&mut self, name: &str, local_variable: LocalVariable) {
+        if self.scopes.is_empty() {
+            self.scopes.push(HashMap::new());
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), local_variable);
+        }
+    }
+
+    /// Declares a named parameter, e.g. of a function, of a `try` or of a `catch` clause
+    ///
+    /// `parameter` the Solang parameter
+    fn declare_parameter(&mut self, parameter: &Parameter) {
+        let Some(name) = &parameter.name else { return };
+        let parameter_type = self.parse_type(&parameter.ty);
+        // function can have storage parameters
+        let storage_pointer = if matches!(parameter.storage, Some(StorageLocation::Storage(_))) {
+            self.parse_storage_pointer_type(&parameter.ty)
+        } else {
+            None
+        };
+
+        self.declare_local(
+            &name.name,
+            LocalVariable {
+                ty: Some(parameter_type),
+                storage_pointer,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Returns the local variable visible under the name, the innermost declaration first
+    ///
+    /// `name` the name of the variable
+    fn find_local(&self, name: &str) -> Option<&LocalVariable> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    /// Returns the struct the local variable points to if it is a storage pointer
+    ///
+    /// `name` the name of the variable
+    fn local_storage_pointer(&self, name: &str) -> Option<Symbol> {
+        self.find_local(name)?.storage_pointer.clone()
+    }
+
+    /// Returns the storage accessed through a local storage pointer to a struct, the storage
+    /// the pointer points into if we know it, otherwise the struct
+    ///
+    /// `name` the name of the variable
+    fn pointer_storage(&self, name: &str) -> Option<Symbol> {
+        let local_variable = self.find_local(name)?;
+        let storage_pointer = local_variable.storage_pointer.as_ref()?;
+        self.structs.get(storage_pointer)?;
+
+        Some(
+            local_variable
+                .storage
+                .clone()
+                .unwrap_or(storage_pointer.clone()),
+        )
+    }
+
+    /// Returns the storage the local storage pointer is an alias of, if we know it
+    ///
+    /// `name` the name of the variable
+    fn local_alias(&self, name: &str) -> Option<StorageAlias> {
+        self.find_local(name)?.alias.clone()
+    }
+
+    /// Returns the storage variable whose element or field is assigned to a storage pointer,
+    /// e.g. `users` of `users[id]`, `positions` of `l.positions[0]` or `balances` of
+    /// `u.balances` where `u` is an alias of `users`
+    ///
+    /// `expression` the expression assigned to the storage pointer
+    fn parse_storage_alias(&self, expression: &SolangExpression) -> Option<StorageAlias> {
+        // we walk down to the root of `root[index].field_0.field_1`
+        let mut path = Vec::default();
+        let mut root = expression;
+
+        loop {
+            match root {
+                SolangExpression::MemberAccess(_, inner, field) => {
+                    path.insert(0, field.name.clone());
+                    root = inner;
+                }
+                SolangExpression::ArraySubscript(_, inner, _)
+                | SolangExpression::Parenthesis(_, inner) => root = inner,
+                _ => break,
+            }
+        }
+
+        let pointer_alias = |storage: Symbol, path: Vec<String>| {
+            let (field, path) = path.split_first()?;
+
+            Some(StorageAlias {
+                call_type: CallType::CallingStoragePointer,
+                storage: storage.field(field),
+                path: path.to_vec(),
+            })
+        };
+
+        match root {
+            SolangExpression::Variable(identifier) => {
+                if let Some(mut alias) = self.local_alias(&identifier.name) {
+                    alias.path.extend(path);
+                    return Some(alias)
+                }
+                if self.find_local(&identifier.name).is_some() {
+                    return pointer_alias(self.pointer_storage(&identifier.name)?, path)
+                }
+                if let Some(MemberType::StorageField(symbol, _)) =
+                    self.members_map.get(&identifier.name)
+                {
+                    return Some(StorageAlias {
+                        call_type: CallType::CallingStorage,
+                        storage: symbol.clone(),
+                        path,
+                    })
+                }
+                None
+            }
+            SolangExpression::FunctionCall(..) => {
+                pointer_alias(self.returned_pointer_storage(root)?, path)
+            }
+            _ => None,
+        }
+    }
+
+    /// Updates the storage a local storage pointer is an alias of when it is assigned
+    ///
+    /// `name` the name of the variable
+    /// `value` the assigned value
+    ///
+    /// Returns true if the variable is a local storage pointer
+    fn realias_local(&mut self, name: &str, value: &SolangExpression) -> bool {
+        if self.local_storage_pointer(name).is_none() {
+            return false
+        }
+        let alias = self.parse_storage_alias(value);
+        if let Some(local_variable) = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            local_variable.alias = alias;
+        }
+
+        true
+    }
+
+    /// Returns the storage a storage pointer is initialized with, if we know it
+    ///
+    /// Pointers returned by a function which returns a storage namespace point into the namespace
+    ///
+    /// `storage_pointer` the struct the pointer points to
+    /// `initial_value` the initial value of the pointer
+    fn parse_pointer_origin(
+        &self,
+        storage_pointer: &Symbol,
+        initial_value: &SolangExpression,
+    ) -> Option<Symbol> {
+        let SolangExpression::FunctionCall(_, function, args) = initial_value else {
+            return None
+        };
+        let arg_types = args
+            .iter()
+            .map(|arg| self.parse_expression_type(arg))
+            .collect::<Vec<_>>();
+        let function_symbol = self.resolve_called_function(function, &arg_types)?;
+        let position = self.storage_namespaces.get(&function_symbol)?;
+
+        // storage namespaces are shared by all the contracts using them
+        Some(Symbol::new("", position, &storage_pointer.member))
+    }
+
+    /// Returns the struct pointed to by the storage pointer returned by a call,
+    /// e.g. `LibX.layout()` or `s()`
+    ///
+    /// `expression` the call expression
+    fn returned_storage_pointer(&self, expression: &SolangExpression) -> Option<Symbol> {
+        let SolangExpression::FunctionCall(_, function, args) = expression else {
+            return None
+        };
+        let arg_types = args
+            .iter()
+            .map(|arg| self.parse_expression_type(arg))
+            .collect::<Vec<_>>();
+        let function_symbol = self.resolve_called_function(function, &arg_types)?;
+
+        self.storage_pointers.get(&function_symbol).cloned()
+    }
+
+    /// Returns the storage accessed through the storage pointer returned by a call, the storage
+    /// namespace of the function if we know it, otherwise the struct
+    ///
+    /// `expression` the call expression, e.g. `s()` of `s().balance`
+    fn returned_pointer_storage(&self, expression: &SolangExpression) -> Option<Symbol> {
+        let storage_pointer = self.returned_storage_pointer(expression)?;
+        self.structs.get(&storage_pointer)?;
+
+        Some(
+            self.parse_pointer_origin(&storage_pointer, expression)
+                .unwrap_or(storage_pointer),
+        )
+    }
+
+    /// Returns the function of this contract, of a library or declared at the file level
+    /// which is called by the expression, e.g. `layout()` or `LibDiamond.diamondStorage()`
+    ///
+    /// `function` the called expression
+    /// `arg_types` the types of the arguments
+    fn resolve_called_function(
+        &self,
+        function: &SolangExpression,
+        arg_types: &[Option<String>],
+    ) -> Option<Symbol> {
+        match function {
+            SolangExpression::Variable(identifier) => {
+                if self.find_local(&identifier.name).is_some() {
+                    return None
+                }
+                let Some(MemberType::Function(overloads)) = self.members_map.get(&identifier.name)
+                else {
+                    return None
+                };
+                Self::select_overload(overloads, Some(arg_types)).map(|(_, symbol)| symbol)
+            }
+            SolangExpression::MemberAccess(_, left, right) => {
+                let SolangExpression::Variable(left_ident) = left.as_ref() else {
+                    return None
+                };
+                if self.find_local(&left_ident.name).is_some()
+                    || self.members_map.contains_key(&left_ident.name)
+                {
+                    return None
+                }
+                Self::select_overload(
+                    &self.find_overloads(&left_ident.name, &right.name),
+                    Some(arg_types),
+                )
+                .map(|(_, symbol)| symbol)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the struct a storage pointer of the type points to, e.g. `Lib.Struct`
+    ///
+    /// `ty` the original Solang type expression of the storage pointer
+    fn parse_storage_pointer_type(&self, ty: &SolangExpression) -> Option<Symbol> {
+        match ty {
+            // @todo handle cases where we store mpping, structs etc.
+            SolangExpression::Variable(identifier) => Some(self.resolve_struct(&identifier.name)),
+            SolangExpression::MemberAccess(_, left, right) => {
+                // @todo handle cases when left is member access etc...
+                if let SolangExpression::Variable(left_ident) = left.as_ref() {
+                    Some(self.symbol(&left_ident.name, &right.name))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
     /// Parses a Sol2Ink function header definition from Solang function definition
     ///
     /// `function_definition` the Solang function definition
@@ -454,7 +1462,13 @@ impl<'a> Parser<'a> {
         &mut self,
         function_definition: &FunctionDefinition,
     ) -> FunctionHeader {
-        let name = self.parse_identifier(&function_definition.name);
+        // receive and fallback functions are not named
+        let name = match function_definition.ty {
+            FunctionTy::Receive => String::from("receive"),
+            FunctionTy::Fallback => String::from("fallback"),
+            _ => self.parse_identifier(&function_definition.name),
+        };
+        let params = self.parse_params(function_definition);
         let modifiers: Vec<Expression> = function_definition
             .attributes
             .iter()
@@ -467,8 +1481,14 @@ impl<'a> Parser<'a> {
                     } else {
                         Vec::default()
                     };
-
-                    Expression::Modifier(parsed_name, parsed_args)
+                    let arg_texts = base
+                        .args
+                        .iter()
+                        .flatten()
+                        .map(|arg| self.expression_text(arg))
+                        .collect();
+
+                    Expression::Modifier(parsed_name, parsed_args, arg_texts)
                 } else {
                     unreachable!("The vec was filtered before");
                 }
@@ -495,8 +1515,22 @@ impl<'a> Parser<'a> {
             )
         });
 
+        let param_names = function_definition
+            .params
+            .iter()
+            .map(|(_, param)| {
+                param
+                    .as_ref()
+                    .and_then(|param| param.name.as_ref())
+                    .map(|name| name.name.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+
         FunctionHeader {
             name,
+            params,
+            param_names,
             external,
             view,
             payable,
@@ -504,6 +1538,22 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Returns the types of the parameters of a function
+    ///
+    /// `function_definition` the Solang function definition
+    fn parse_params(&self, function_definition: &FunctionDefinition) -> Vec<String> {
+        function_definition
+            .params
+            .iter()
+            .map(|(_, param)| {
+                param
+                    .as_ref()
+                    .map(|param| self.parse_type(&param.ty))
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
     /// Parses a Solang statement enum variant to Sol2Ink statement enum variant
     ///
     /// `statement` the original Solang statement enum variant
@@ -517,23 +1567,12 @@ impl<'a> Parser<'a> {
                 unchecked: _,
                 statements,
             } => {
-                self.current_depth += 1;
+                self.scopes.push(HashMap::new());
                 let out = statements
                     .iter()
                     .flat_map(|statement| self.parse_statement(statement).unwrap_or_default())
                     .collect::<Vec<_>>();
-                if let Some(local_storage_pointers_declared) = self
-                    .local_storage_pointers_declared
-                    .get(&self.current_depth)
-                {
-                    for local_storage_pointer_declared in local_storage_pointers_declared {
-                        self.local_storage_pointers
-                            .remove(local_storage_pointer_declared);
-                    }
-                    self.local_storage_pointers_declared
-                        .remove(&self.current_depth);
-                }
-                self.current_depth -= 1;
+                self.scopes.pop();
                 out
             }
             SolangStatement::Assembly {
@@ -541,82 +1580,89 @@ impl<'a> Parser<'a> {
                 dialect: _,
                 flags: _,
                 block,
-            } => {
-                block
-                    .statements
-                    .iter()
-                    .flat_map(|statement| self.parse_yul_statement(&statement.clone()))
-                    .collect()
-            }
+            } => self.parse_yul_block(block),
             SolangStatement::If(_, expression, if_true, if_false) => {
                 let mut parsed_expression = self.parse_expression(expression);
+                // `if (msg.sender != owner) revert Unauthorized()` guards the access
+                if Self::reverts(if_true) {
+                    parsed_expression.extend(self.parse_guard(expression, true));
+                }
                 let parsed_if_true = self.parse_statement(if_true)?;
                 let parsed_if_false = if_false
                     .as_ref()
                     .map(|statement| self.parse_statement(statement).unwrap_or_default())
                     .unwrap_or_default();
 
-                parsed_expression.extend(parsed_if_true);
-                parsed_expression.extend(parsed_if_false);
+                parsed_expression.extend(Self::branches(vec![parsed_if_true, parsed_if_false]));
 
                 parsed_expression
             }
             SolangStatement::While(_, expression, statement) => {
                 let mut parsed_expression = self.parse_expression(expression);
-                let parsed_statement = self.parse_statement(statement)?;
+                let mut parsed_statement = self.parse_statement(statement)?;
 
-                parsed_expression.extend(parsed_statement);
+                // the condition is evaluated again after each iteration
+                parsed_statement.extend(parsed_expression.clone());
+                parsed_expression.extend(Self::repeated(parsed_statement));
 
                 parsed_expression
             }
+            // `_` of a modifier, the body of the modified function runs there
+            SolangStatement::Expression(_, SolangExpression::Variable(identifier))
+                if identifier.name == "_" =>
+            {
+                vec![Call::Placeholder]
+            }
             SolangStatement::Expression(_, expression) => self.parse_expression(expression),
             SolangStatement::VariableDefinition(_, definition, initial_value_maybe) => {
-                if definition
-                    .storage
-                    .clone()
-                    .filter(|storage| matches!(storage, StorageLocation::Storage(_)))
-                    .is_some()
-                {
-                    let mut local_storage_pointers_declared = self
-                        .local_storage_pointers_declared
-                        .get(&self.current_depth)
-                        .unwrap_or(&vec![])
-                        .clone();
-                    match definition.ty.clone() {
-                        // @todo handle cases where we store mpping, structs etc.
-                        SolangExpression::Variable(variable_type) => {
-                            let parsed_variable_type = self.parse_identifier(&Some(variable_type));
-                            let full_variable_type =
-                                format!("{}_{}", self.current_contract, parsed_variable_type);
-                            let variable_name = self.parse_identifier(&definition.name.clone());
-                            self.local_storage_pointers
-                                .insert(variable_name.clone(), full_variable_type);
-                            local_storage_pointers_declared.push(variable_name);
-                        }
-                        SolangExpression::MemberAccess(_, left, right) => {
-                            if let SolangExpression::Variable(left_ident) = *left.clone() {
-                                let parsed_left = self.parse_identifier(&Some(left_ident));
-                                let parsed_right = self.parse_identifier(&Some(right));
-                                let variable_name = self.parse_identifier(&definition.name.clone());
-                                self.local_storage_pointers.insert(
-                                    variable_name.clone(),
-                                    format!("{parsed_left}_{parsed_right}"),
-                                );
-                                local_storage_pointers_declared.push(variable_name);
-                            }
-                        }
-                        _ => (),
-                    };
-                    self.local_storage_pointers_declared
-                        .insert(self.current_depth, local_storage_pointers_declared);
-                }
-
-                initial_value_maybe
+                // the initial value may access a member shadowed by the declaration
+                let parsed_initial_value = initial_value_maybe
                     .as_ref()
                     .map(|expression| self.parse_expression(expression))
-                    .unwrap_or_default()
+                    .unwrap_or_default();
+
+                let variable_name = self.parse_identifier(&definition.name);
+                let variable_type = self.parse_type(&definition.ty);
+                // the function returning the pointer knows the struct better than the declared type,
+                // e.g. `Layout` of `Layout storage l = LibX.layout()` is declared in `LibX`
+                let storage_pointer =
+                    if matches!(definition.storage, Some(StorageLocation::Storage(_))) {
+                        initial_value_maybe
+                            .as_ref()
+                            .and_then(|initial_value| self.returned_storage_pointer(initial_value))
+                            .or_else(|| self.parse_storage_pointer_type(&definition.ty))
+                    } else {
+                        None
+                    };
+                let storage = storage_pointer.as_ref().and_then(|storage_pointer| {
+                    self.parse_pointer_origin(storage_pointer, initial_value_maybe.as_ref()?)
+                });
+                let alias = if matches!(definition.storage, Some(StorageLocation::Storage(_))) {
+                    initial_value_maybe
+                        .as_ref()
+                        .and_then(|initial_value| self.parse_storage_alias(initial_value))
+                } else {
+                    None
+                };
+
+                self.declare_local(
+                    &variable_name,
+                    LocalVariable {
+                        ty: Some(variable_type),
+                        storage_pointer,
+                        storage,
+                        slot: None,
+                        alias,
+                        parameter: false,
+                    },
+                );
+
+                parsed_initial_value
             }
             SolangStatement::For(_, variable_definition, condition, on_pass, body) => {
+                // the loop variable is visible in the whole loop
+                self.scopes.push(HashMap::new());
+
                 let mut parsed_variable_definition = variable_definition
                     .as_ref()
                     .map(|statement| self.parse_statement(statement).unwrap_or_default())
@@ -636,57 +1682,179 @@ impl<'a> Parser<'a> {
                     .map(|result| result.unwrap())
                     .unwrap_or_default();
 
+                self.scopes.pop();
+
+                let mut parsed_iteration = parsed_body;
+                parsed_iteration.extend(parsed_on_pass);
+                parsed_iteration.extend(parsed_condition.clone());
+
                 parsed_variable_definition.extend(parsed_condition);
-                parsed_variable_definition.extend(parsed_on_pass);
-                parsed_variable_definition.extend(parsed_body);
+                parsed_variable_definition.extend(Self::repeated(parsed_iteration));
 
                 parsed_variable_definition
             }
             SolangStatement::DoWhile(_, body, condition) => {
-                let mut parsed_condition = self.parse_expression(condition);
-                let parsed_body = self.parse_statement(body)?;
+                let parsed_condition = self.parse_expression(condition);
+                let mut parsed_body = self.parse_statement(body)?;
 
-                parsed_condition.extend(parsed_body);
+                parsed_body.extend(parsed_condition);
 
-                parsed_condition
+                Self::repeated(parsed_body)
             }
             SolangStatement::Return(_, expression) => {
-                expression
+                let mut parsed_expression = expression
                     .as_ref()
                     .map(|expression| self.parse_expression(expression))
-                    .unwrap_or_default()
+                    .unwrap_or_default();
+                parsed_expression.push(Call::Return);
+                parsed_expression
+            }
+            SolangStatement::Try(_, expression, returns, catch_clauses) => {
+                let mut parsed_try = self.parse_expression(expression);
+                // either the success block or one of the catch clauses is executed
+                let mut paths = Vec::default();
+
+                // returned and caught variables are visible only in their blocks
+                if let Some((returns, statement)) = returns {
+                    self.scopes.push(HashMap::new());
+                    for parameter in returns
+                        .iter()
+                        .filter_map(|(_, parameter)| parameter.as_ref())
+                    {
+                        self.declare_parameter(parameter);
+                    }
+                    paths.push(self.parse_statement(statement)?);
+                    self.scopes.pop();
+                }
+
+                for catch_clause in catch_clauses.iter() {
+                    let (parameter, statement) = match catch_clause {
+                        CatchClause::Simple(_, parameter, statement) => {
+                            (parameter.as_ref(), statement)
+                        }
+                        CatchClause::Named(_, _, parameter, statement) => {
+                            (Some(parameter), statement)
+                        }
+                    };
+                    self.scopes.push(HashMap::new());
+                    if let Some(parameter) = parameter {
+                        self.declare_parameter(parameter);
+                    }
+                    paths.push(self.parse_statement(statement)?);
+                    self.scopes.pop();
+                }
+
+                parsed_try.extend(Self::branches(paths));
+
+                parsed_try
+            }
+            SolangStatement::Revert(_, error, args) => {
+                let mut parsed_args = self.parse_expression_vec(args);
+                if let Some(error) = error {
+                    // the error may be declared in another contract, e.g. `revert IErrors.Unauthorized()`
+                    parsed_args.push(Call::Revert(
+                        self.parse_revert_error(error, !args.is_empty()),
+                    ));
+                } else {
+                    // `revert()` or `revert("reason")`
+                    parsed_args.push(Call::Revert(None));
+                }
+                parsed_args
+            }
+            SolangStatement::RevertNamedArgs(_, error, args) => {
+                let mut parsed_args = args
+                    .iter()
+                    .flat_map(|arg| self.parse_expression(&arg.expr))
+                    .collect::<Vec<_>>();
+                if let Some(error) = error {
+                    parsed_args.push(Call::Revert(
+                        self.parse_revert_error(error, !args.is_empty()),
+                    ));
+                } else {
+                    parsed_args.push(Call::Revert(None));
+                }
+                parsed_args
+            }
+            SolangStatement::Emit(_, expression) => {
+                if let SolangExpression::FunctionCall(_, event, args) = expression {
+                    let mut parsed_args = self.parse_expression_vec(args);
+                    // the event may be declared in another contract, e.g. `emit IERC20.Transfer()`
+                    let event_name = match event.as_ref() {
+                        SolangExpression::Variable(identifier) => identifier.name.clone(),
+                        SolangExpression::MemberAccess(_, _, identifier) => identifier.name.clone(),
+                        _ => return Ok(parsed_args),
+                    };
+                    parsed_args.push(Call::Emit(self.symbol(&self.current_contract, &event_name)));
+                    parsed_args
+                } else {
+                    self.parse_expression(expression)
+                }
             }
-            SolangStatement::Try(_, expression, _, _) => self.parse_expression(expression),
             _ => Vec::default(),
         })
     }
 
-    fn parse_yul_statement(&self, yul_statement: &YulStatement) -> Vec<Call> {
+    fn parse_yul_statement(&mut self, yul_statement: &YulStatement) -> Vec<Call> {
         match yul_statement {
             YulStatement::Assign(_, yul_expressions, yul_expression) => {
-                let mut expressions = yul_expressions
-                    .iter()
-                    .flat_map(|yul_expression| self.parse_yul_expression(&yul_expression.clone()))
-                    .collect::<Vec<_>>();
                 let expression = self.parse_yul_expression(&yul_expression.clone());
+                let slot = self.parse_yul_slot(yul_expression);
+
+                // assigned variables are not read, `pointer.slot := position` only moves the pointer
+                for assigned in yul_expressions.iter() {
+                    if let YulExpression::Variable(identifier) = assigned {
+                        if let Some(local_variable) = self
+                            .scopes
+                            .iter_mut()
+                            .rev()
+                            .find_map(|scope| scope.get_mut(&identifier.name))
+                        {
+                            local_variable.slot =
+                                slot.clone().filter(|_| yul_expressions.len() == 1);
+                        }
+                    }
+                }
 
-                expressions.extend(expression);
-
-                expressions
+                expression
             }
-            YulStatement::If(_, yul_expression, yul_block) => {
-                let mut yul_expression = self.parse_yul_expression(&yul_expression.clone());
-                let yul_block = yul_block
-                    .statements
-                    .iter()
-                    .flat_map(|statement| self.parse_yul_statement(&statement.clone()))
-                    .collect::<Vec<_>>();
+            YulStatement::VariableDeclaration(_, identifiers, yul_expression) => {
+                let expression = yul_expression
+                    .as_ref()
+                    .map(|yul_expression| self.parse_yul_expression(yul_expression))
+                    .unwrap_or_default();
+                let slot = yul_expression
+                    .as_ref()
+                    .and_then(|yul_expression| self.parse_yul_slot(yul_expression))
+                    .filter(|_| identifiers.len() == 1);
+
+                // the variables are visible after the declaration
+                for identifier in identifiers.iter() {
+                    self.declare_local(
+                        &identifier.id.name,
+                        LocalVariable {
+                            slot: slot.clone(),
+                            ..Default::default()
+                        },
+                    );
+                }
 
-                yul_expression.extend(yul_block);
+                expression
+            }
+            YulStatement::FunctionCall(yul_function_call) => {
+                self.parse_yul_function_call(yul_function_call)
+            }
+            YulStatement::If(_, yul_expression, yul_block) => {
+                let mut yul_expression = self.parse_yul_expression(&yul_expression.clone());
+                let yul_block = self.parse_yul_block(yul_block);
+
+                yul_expression.extend(Self::branches(vec![yul_block]));
 
                 yul_expression
             }
             YulStatement::For(yul_for) => {
+                // variables declared in the init block are visible in the whole loop
+                self.scopes.push(HashMap::new());
+
                 let mut init_block = yul_for
                     .init_block
                     .statements
@@ -696,119 +1864,77 @@ impl<'a> Parser<'a> {
 
                 let expression = self.parse_yul_expression(&yul_for.condition.clone());
 
-                let post_block = yul_for
-                    .post_block
-                    .statements
-                    .iter()
-                    .flat_map(|statement| self.parse_yul_statement(&statement.clone()))
-                    .collect::<Vec<_>>();
+                let post_block = self.parse_yul_block(&yul_for.post_block);
 
-                let execution_block = yul_for
-                    .execution_block
-                    .statements
-                    .iter()
-                    .flat_map(|statement| self.parse_yul_statement(&statement.clone()))
-                    .collect::<Vec<_>>();
+                let execution_block = self.parse_yul_block(&yul_for.execution_block);
+
+                self.scopes.pop();
+
+                let mut iteration = execution_block;
+                iteration.extend(post_block);
+                iteration.extend(expression.clone());
 
                 init_block.extend(expression);
-                init_block.extend(post_block);
-                init_block.extend(execution_block);
+                init_block.extend(Self::repeated(iteration));
 
                 init_block
             }
             YulStatement::Switch(yul_switch) => {
                 let mut condition = self.parse_yul_expression(&yul_switch.condition.clone());
-                let cases = yul_switch
+                let mut cases = yul_switch
                     .cases
                     .iter()
-                    .flat_map(|case| {
-                        match case {
-                            solang_parser::pt::YulSwitchOptions::Case(
-                                _,
-                                yul_expression,
-                                yul_block,
-                            ) => {
-                                let mut yul_expression =
-                                    self.parse_yul_expression(&yul_expression.clone());
-
-                                let yul_block = yul_block
-                                    .statements
-                                    .iter()
-                                    .flat_map(|statement| {
-                                        self.parse_yul_statement(&statement.clone())
-                                    })
-                                    .collect::<Vec<_>>();
-
-                                yul_expression.extend(yul_block);
-
-                                yul_expression
-                            }
-                            solang_parser::pt::YulSwitchOptions::Default(_, yul_block) => {
-                                yul_block
-                                    .statements
-                                    .iter()
-                                    .flat_map(|statement| {
-                                        self.parse_yul_statement(&statement.clone())
-                                    })
-                                    .collect::<Vec<_>>()
-                            }
-                        }
-                    })
-                    .collect::<Vec<_>>();
-                let default = yul_switch
-                    .default
-                    .clone()
+                    .chain(yul_switch.default.iter())
                     .map(|case| {
                         match case {
-                            solang_parser::pt::YulSwitchOptions::Case(
-                                _,
-                                yul_expression,
-                                yul_block,
-                            ) => {
+                            YulSwitchOptions::Case(_, yul_expression, yul_block) => {
                                 let mut yul_expression =
                                     self.parse_yul_expression(&yul_expression.clone());
 
-                                let yul_block = yul_block
-                                    .statements
-                                    .iter()
-                                    .flat_map(|statement| {
-                                        self.parse_yul_statement(&statement.clone())
-                                    })
-                                    .collect::<Vec<_>>();
+                                let yul_block = self.parse_yul_block(yul_block);
 
                                 yul_expression.extend(yul_block);
 
                                 yul_expression
                             }
-                            solang_parser::pt::YulSwitchOptions::Default(_, yul_block) => {
-                                yul_block
-                                    .statements
-                                    .iter()
-                                    .flat_map(|statement| {
-                                        self.parse_yul_statement(&statement.clone())
-                                    })
-                                    .collect::<Vec<_>>()
+                            YulSwitchOptions::Default(_, yul_block) => {
+                                self.parse_yul_block(yul_block)
                             }
                         }
                     })
-                    .unwrap_or_default();
+                    .collect::<Vec<_>>();
+                // without `default` none of the cases may match
+                if yul_switch.default.is_none() {
+                    cases.push(Vec::default());
+                }
 
-                condition.extend(cases);
-                condition.extend(default);
+                condition.extend(Self::branches(cases));
 
                 condition
             }
-            YulStatement::Block(yul_block) => {
-                yul_block
-                    .statements
-                    .iter()
-                    .flat_map(|statement| self.parse_yul_statement(&statement.clone()))
-                    .collect()
-            }
+            YulStatement::Block(yul_block) => self.parse_yul_block(yul_block),
             _ => Vec::default(),
         }
     }
 
+    /// Parses the statements of a Yul block, the variables declared in the block are not
+    /// visible outside of it
+    ///
+    /// `yul_block` the original Solang Yul block
+    fn parse_yul_block(&mut self, yul_block: &YulBlock) -> Vec<Call> {
+        self.scopes.push(HashMap::new());
+
+        let calls = yul_block
+            .statements
+            .iter()
+            .flat_map(|statement| self.parse_yul_statement(&statement.clone()))
+            .collect();
+
+        self.scopes.pop();
+
+        calls
+    }
+
     fn parse_yul_expression(&self, yul_expression: &YulExpression) -> Vec<Call> {
         match yul_expression {
             YulExpression::BoolLiteral(..)
@@ -818,30 +1944,24 @@ impl<'a> Parser<'a> {
             | YulExpression::StringLiteral(..) => Vec::default(),
             YulExpression::Variable(identifier) => {
                 let parsed_identifier = self.parse_identifier(&Some(identifier.clone()));
-                if let Some(member) = self.members_map.get(&parsed_identifier) {
+                if self.find_local(&parsed_identifier).is_some() {
+                    // local variables shadow the members
+                    Vec::default()
+                } else if let Some(member) = self.members_map.get(&parsed_identifier) {
                     match member {
-                        MemberType::StorageField(contract_name) => {
-                            vec![Call::ReadStorage(
-                                CallType::CallingStorage,
-                                contract_name.clone(),
-                                parsed_identifier,
-                            )]
+                        MemberType::StorageField(symbol, _) => {
+                            vec![Call::ReadStorage(CallType::CallingStorage, symbol.clone())]
                         }
-                        MemberType::Function(function_header, contract_name) => {
+                        // immutables can not be accessed in assembly
+                        MemberType::Immutable(_) => Vec::default(),
+                        MemberType::Function(overloads) => {
+                            let (function_header, symbol) = &overloads[0];
                             let call_type = CallType::CallingFunction;
 
                             if function_header.view {
-                                vec![Call::Read(
-                                    call_type,
-                                    contract_name.clone(),
-                                    parsed_identifier,
-                                )]
+                                vec![Call::Read(call_type, symbol.clone())]
                             } else {
-                                vec![Call::Write(
-                                    call_type,
-                                    contract_name.clone(),
-                                    parsed_identifier,
-                                )]
+                                vec![Call::Write(call_type, symbol.clone())]
                             }
                         }
                     }
@@ -850,15 +1970,150 @@ impl<'a> Parser<'a> {
                 }
             }
             YulExpression::FunctionCall(yul_function_call) => {
-                yul_function_call
-                    .arguments
-                    .iter()
-                    .flat_map(|arg| self.parse_yul_expression(&arg.clone()))
-                    .collect()
+                self.parse_yul_function_call(yul_function_call)
+            }
+            // `x.slot` and `x.offset` are positions in the storage, they do not access it
+            YulExpression::SuffixAccess(..) => Vec::default(),
+        }
+    }
+
+    /// Parses a call of a Yul function, storage is accessed by `sload` and `sstore`,
+    /// transient storage by `tload` and `tstore`
+    ///
+    /// `yul_function_call` the original Solang Yul function call
+    fn parse_yul_function_call(&self, yul_function_call: &YulFunctionCall) -> Vec<Call> {
+        let mut calls = yul_function_call
+            .arguments
+            .iter()
+            .flat_map(|arg| self.parse_yul_expression(arg))
+            .collect::<Vec<_>>();
+
+        let call_type = match yul_function_call.id.name.as_str() {
+            "delegatecall" => {
+                calls.push(Call::Delegate);
+                return calls
+            }
+            "revert" => {
+                calls.push(Call::Revert(None));
+                return calls
+            }
+            "call" => {
+                // `call(gas, to, value, ...)` sends ETH unless the value is zero
+                if let [_, to, value, ..] = yul_function_call.arguments.as_slice() {
+                    if !Self::is_yul_zero(value) {
+                        calls.push(Call::SendValue(
+                            String::from("call"),
+                            self.parse_yul_recipient(to),
+                        ));
+                    }
+                }
+                calls.push(Call::AddressCall(String::from("call")));
+                return calls
+            }
+            "selfdestruct" => {
+                if let [recipient] = yul_function_call.arguments.as_slice() {
+                    calls.push(Call::SendValue(
+                        String::from("selfdestruct"),
+                        self.parse_yul_recipient(recipient),
+                    ));
+                }
+                return calls
+            }
+            // the code of the created contract is not known in assembly
+            "create" | "create2" => {
+                calls.push(Call::Create(None, yul_function_call.id.name.clone()));
+                return calls
+            }
+            "sload" | "sstore" => CallType::CallingStorage,
+            "tload" | "tstore" => CallType::CallingTransientStorage,
+            _ => return calls,
+        };
+
+        // @todo slots computed from constants or hashes are not known
+        if let Some(symbol) = yul_function_call
+            .arguments
+            .first()
+            .and_then(|slot| self.parse_yul_slot(slot))
+        {
+            calls.push(match yul_function_call.id.name.as_str() {
+                "sload" | "tload" => Call::ReadStorage(call_type, symbol),
+                _ => Call::WriteStorage(call_type, symbol),
+            });
+        }
+
+        calls
+    }
+
+    /// Returns the recipient of the ETH sent to an address in assembly, e.g. `caller()`
+    ///
+    /// `yul_expression` the original Solang Yul expression of the receiving address
+    fn parse_yul_recipient(&self, yul_expression: &YulExpression) -> Recipient {
+        match yul_expression {
+            YulExpression::FunctionCall(yul_function_call) => {
+                match (
+                    yul_function_call.id.name.as_str(),
+                    yul_function_call.arguments.as_slice(),
+                ) {
+                    ("caller", []) => return Recipient::Sender,
+                    ("sload", [slot]) => {
+                        if let Some(symbol) = self.parse_yul_slot(slot) {
+                            return Recipient::Storage(symbol.member)
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            YulExpression::Variable(identifier)
+                if self
+                    .find_local(&identifier.name)
+                    .is_some_and(|local| local.parameter) =>
+            {
+                return Recipient::Parameter(identifier.name.clone())
             }
-            YulExpression::SuffixAccess(_, expression, _) => {
-                self.parse_yul_expression(&expression.as_ref().clone())
+            _ => (),
+        }
+
+        Recipient::Other(yul_expression.to_string())
+    }
+
+    /// Returns `true` if the Yul expression is the literal zero, e.g. `0` or `0x00`
+    ///
+    /// `yul_expression` the original Solang Yul expression
+    fn is_yul_zero(yul_expression: &YulExpression) -> bool {
+        match yul_expression {
+            YulExpression::NumberLiteral(_, value, ..) => value.chars().all(|digit| digit == '0'),
+            YulExpression::HexNumberLiteral(_, value, _) => {
+                value
+                    .trim_start_matches("0x")
+                    .chars()
+                    .all(|digit| digit == '0')
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the storage field whose slot the Yul expression evaluates to, e.g. `x.slot`
+    /// or a variable holding it
+    ///
+    /// `yul_expression` the original Solang Yul expression
+    fn parse_yul_slot(&self, yul_expression: &YulExpression) -> Option<Symbol> {
+        match yul_expression {
+            YulExpression::SuffixAccess(_, expression, suffix) if suffix.name == "slot" => {
+                let YulExpression::Variable(identifier) = expression.as_ref() else {
+                    return None
+                };
+                // @todo slots of storage pointers point at the first field of the struct
+                if self.find_local(&identifier.name).is_some() {
+                    return None
+                }
+
+                match self.members_map.get(&identifier.name) {
+                    Some(MemberType::StorageField(symbol, _)) => Some(symbol.clone()),
+                    _ => None,
+                }
             }
+            YulExpression::Variable(identifier) => self.find_local(&identifier.name)?.slot.clone(),
+            _ => None,
         }
     }
 
@@ -888,25 +2143,13 @@ impl<'a> Parser<'a> {
             | SolangExpression::PostDecrement(_, expression)
             | SolangExpression::PreIncrement(_, expression)
             | SolangExpression::PreDecrement(_, expression)
-            | SolangExpression::Delete(_, expression) => {
-                boxed_expression!(parsed_expression, expression)
-                    .iter()
-                    .map(|call| {
-                        match call.clone() {
-                            Call::Read(call_type, contract, read) => {
-                                Call::Read(call_type, contract, read)
-                            }
-                            Call::ReadStorage(call_type, contract, read) => {
-                                Call::WriteStorage(call_type, contract, read)
-                            }
-                            Call::Write(..) | Call::WriteStorage(..) => call.clone(),
-                            _ => unreachable!("This should be unreachable"),
-                        }
-                    })
-                    .collect()
+            | SolangExpression::Delete(_, expression) => self.parse_assigned_expression(expression),
+            SolangExpression::New(_, expression) => {
+                let mut parsed_expression = self.parse_expression(expression);
+                parsed_expression.extend(Self::parse_creation(expression));
+                parsed_expression
             }
-            SolangExpression::New(_, expression)
-            | SolangExpression::Parenthesis(_, expression)
+            SolangExpression::Parenthesis(_, expression)
             | SolangExpression::Not(_, expression)
             | SolangExpression::BitwiseNot(_, expression)
             | SolangExpression::UnaryPlus(_, expression)
@@ -960,66 +2203,69 @@ impl<'a> Parser<'a> {
                 exp
             }
             SolangExpression::MemberAccess(_, left, right) => {
-                let mut expressions = boxed_expression!(parsed_expression, left);
                 let parsed_right = self.parse_identifier(&Some(right.clone()));
-                let mut success = false;
-
-                if let SolangExpression::Variable(left_ident) = *left.clone() {
-                    let parsed_left = self.parse_identifier(&Some(left_ident.clone()));
 
-                    if let Some(storage_pointer) = self.local_storage_pointers.get(&parsed_left) {
-                        // @todo right can be lib function of this struct
-
-                        if self.structs.get(storage_pointer).is_some() {
-                            expressions.extend(vec![Call::ReadStorage(
-                                CallType::CallingStoragePointer,
-                                storage_pointer.clone(),
-                                parsed_right.clone(),
-                            )]);
-                            success = true;
+                // calls of implementations in the bases are resolved once we know the linearization
+                if let SolangExpression::Variable(left_ident) = left.as_ref() {
+                    if let Some(call) =
+                        self.parse_base_access(&left_ident.name, &parsed_right, None)
+                    {
+                        return vec![call]
+                    }
+                }
 
-                            let current_storage_maybe =
-                                self.storage_access.get(&storage_pointer.clone());
+                let mut expressions = boxed_expression!(parsed_expression, left);
+                let mut success = false;
 
-                            if let Some(current_storage) = current_storage_maybe {
-                                let mut new_storage_access = vec![parsed_right.clone()];
-                                new_storage_access.extend(current_storage.clone());
-                                self.storage_access
-                                    .insert(storage_pointer.clone(), new_storage_access);
-                            } else {
-                                self.storage_access
-                                    .insert(storage_pointer.clone(), vec![parsed_right.clone()]);
-                            }
-                        }
+                // @todo right can be lib function of this struct
+                let (alias, storage) = match left.as_ref() {
+                    SolangExpression::Variable(left_ident) => {
+                        let parsed_left = self.parse_identifier(&Some(left_ident.clone()));
+                        (
+                            self.local_alias(&parsed_left),
+                            self.pointer_storage(&parsed_left),
+                        )
                     }
+                    // members of the pointer returned by a function, e.g. `s().balance`
+                    SolangExpression::FunctionCall(..) => {
+                        (None, self.returned_pointer_storage(left))
+                    }
+                    _ => (None, None),
+                };
+
+                if let Some(alias) = alias {
+                    // fields of the aliased elements are accessed as the storage variable
+                    expressions.push(Call::ReadStorage(alias.call_type, alias.storage));
+                    success = true;
+                } else if let Some(storage) = storage {
+                    expressions.extend(vec![Call::ReadStorage(
+                        CallType::CallingStoragePointer,
+                        storage.field(&parsed_right),
+                    )]);
+                    success = true;
+
+                    self.record_storage_access(&storage, &parsed_right);
                 }
 
                 if !success {
                     if let Some(member_type) = self.members_map.get(&parsed_right) {
                         match member_type {
-                            MemberType::StorageField(contract_name) => {
+                            MemberType::StorageField(symbol, _) => {
                                 expressions.extend(vec![Call::ReadStorage(
                                     CallType::CallingStorage,
-                                    contract_name.clone(),
-                                    parsed_right,
+                                    symbol.clone(),
+                                )])
+                            }
+                            MemberType::Immutable(symbol) => {
+                                expressions.extend(vec![Call::ReadStorage(
+                                    CallType::CallingImmutable,
+                                    symbol.clone(),
                                 )])
                             }
-                            MemberType::Function(function_header, contract_name) => {
-                                let call_type = CallType::CallingFunction;
-
-                                if function_header.view {
-                                    expressions.extend(vec![Call::Read(
-                                        call_type,
-                                        contract_name.clone(),
-                                        parsed_right,
-                                    )])
-                                } else {
-                                    expressions.extend(vec![Call::Write(
-                                        call_type,
-                                        contract_name.clone(),
-                                        parsed_right,
-                                    )])
-                                }
+                            MemberType::Function(overloads) => {
+                                let (function_header, symbol) = &overloads[0];
+                                expressions
+                                    .push(self.parse_function_access(function_header, symbol))
                             }
                         }
                     }
@@ -1028,22 +2274,156 @@ impl<'a> Parser<'a> {
                 expressions
             }
             SolangExpression::FunctionCall(_, function, args) => {
-                // First we will handle case when we call a Library function of a storage pointer struct
+                // calls with value or gas are calls of the function inside the block
+                let (function, sends_value) = match function.as_ref() {
+                    SolangExpression::FunctionCallBlock(_, function, block) => {
+                        (function, Self::has_call_option(block, "value"))
+                    }
+                    _ => (function, false),
+                };
+
+                // First we will handle case when we clone a contract, e.g. `Clones.clone(implementation)`,
+                // the cloned contract is not known from its address
+                if let SolangExpression::MemberAccess(_, library, member) = function.as_ref() {
+                    if matches!(library.as_ref(), SolangExpression::Variable(identifier)
+                        if ["Clones", "LibClone"].contains(&identifier.name.as_str()))
+                        && member.name.starts_with("clone")
+                    {
+                        let mut parsed_args = self.parse_expression_vec(args);
+                        parsed_args.push(Call::Create(None, member.name.clone()));
+                        return parsed_args
+                    }
+                }
+
+                // overloads of the called function are told apart by the types of the arguments
+                let arg_types = args
+                    .iter()
+                    .map(|arg| self.parse_expression_type(arg))
+                    .collect::<Vec<_>>();
+
+                // Then we will handle case when we call a function of this contract or its bases
+                if let Some(parsed_call) = self.parse_internal_call(function, &arg_types) {
+                    let mut parsed_args = self.parse_expression_vec(args);
+                    // the body of `_checkRole(role)` only checks its parameter, so the role is
+                    // taken from the argument, the check of `_checkOwner()` is the one of its body
+                    if let Some(guard) = self.parse_guard_call(function, args) {
+                        if !matches!(guard, Call::Guard(ref owner) if owner == "owner()") {
+                            parsed_args.push(guard);
+                        }
+                    }
+                    parsed_args.push(parsed_call);
+                    return parsed_args
+                }
+
+                // Then we will handle case when we require or assert a condition, it may guard
+                // the access and revert with a custom error
+                if let (SolangExpression::Variable(identifier), [condition, rest @ ..]) =
+                    (function.as_ref(), args.as_slice())
+                {
+                    if identifier.name == "require" || identifier.name == "assert" {
+                        let mut parsed_args = self.parse_expression_vec(args);
+                        if identifier.name == "require" {
+                            parsed_args.extend(self.parse_guard(condition, false));
+                        }
+                        let error = rest.first().and_then(|error| self.parse_error_call(error));
+                        // only the failing path reverts
+                        parsed_args.extend(Self::branches(vec![vec![Call::Revert(error)]]));
+                        return parsed_args
+                    }
+                }
+
+                // Then we will handle case when we push to or pop from a storage array or bytes
+                if let Some(mut parsed_call) = self.parse_array_mutation(function) {
+                    parsed_call.extend(self.parse_expression_vec(args));
+                    return parsed_call
+                }
+
+                // Then we will handle case when we forward the call to an implementation
+                if let SolangExpression::MemberAccess(_, target, member) = function.as_ref() {
+                    if member.name == "delegatecall" {
+                        let mut parsed_call = self.parse_expression(target);
+                        parsed_call.extend(self.parse_expression_vec(args));
+                        parsed_call.push(Call::Delegate);
+                        return parsed_call
+                    }
+                }
+
+                // Then we will handle case when we call a function attached with `using for`
+                if let Some(mut parsed_call) = self.parse_bound_call(function, &arg_types) {
+                    parsed_call.extend(self.parse_expression_vec(args));
+                    return parsed_call
+                }
+
+                // Then we will handle case when we call a function of another contract
+                if let Some(mut parsed_call) = self.parse_external_call(function, &arg_types) {
+                    parsed_call.extend(self.parse_expression_vec(args));
+                    if let (SolangExpression::MemberAccess(_, target, _), true) =
+                        (function.as_ref(), sends_value)
+                    {
+                        parsed_call.push(Call::SendValue(
+                            String::from("call"),
+                            self.parse_recipient(target),
+                        ));
+                    }
+                    return parsed_call
+                }
+
+                // Then we will handle case when we send value to or call an address, e.g.
+                // `to.transfer(amount)` or `to.call{value: amount}("")`
+                if let (SolangExpression::MemberAccess(_, target, member), [_]) =
+                    (function.as_ref(), args.as_slice())
+                {
+                    if ["transfer", "send", "call"].contains(&member.name.as_str()) {
+                        let mut parsed_call = self.parse_expression(target);
+                        parsed_call.extend(self.parse_expression_vec(args));
+                        if member.name != "call" || sends_value {
+                            parsed_call.push(Call::SendValue(
+                                member.name.clone(),
+                                self.parse_recipient(target),
+                            ));
+                        }
+                        parsed_call.push(Call::AddressCall(member.name.clone()));
+                        return parsed_call
+                    }
+                }
+
+                // Then we will handle case when we destroy the contract sending its balance away
+                if let (SolangExpression::Variable(identifier), [recipient]) =
+                    (function.as_ref(), args.as_slice())
+                {
+                    if identifier.name == "selfdestruct" {
+                        let mut parsed_call = self.parse_expression(recipient);
+                        parsed_call.push(Call::SendValue(
+                            identifier.name.clone(),
+                            self.parse_recipient(recipient),
+                        ));
+                        return parsed_call
+                    }
+                }
+
+                // Then we will handle case when we call a Library function of a storage pointer struct
 
                 if let SolangExpression::MemberAccess(_, left, right) = *function.clone() {
                     // if on the left we have a variable definition
                     if let SolangExpression::Variable(left_ident) = *left.clone() {
                         let parsed_left = self.parse_identifier(&Some(left_ident.clone()));
                         // if on the left we have a storage pointer
-                        if let Some(storage_pointer) =
-                            self.local_storage_pointers.get(&parsed_left).cloned()
-                        {
+                        if let Some(storage_pointer) = self.local_storage_pointer(&parsed_left) {
                             // on the right side we have the function name
                             let parsed_right = self.parse_identifier(&Some(right.clone()));
 
                             // we have the Library function
                             let mut parsed_args = self.parse_expression_vec(args);
-                            parsed_args.push(Call::Library(storage_pointer.clone(), parsed_right));
+                            let signature = self.find_pointer_function(
+                                &storage_pointer,
+                                &parsed_right,
+                                &arg_types,
+                            );
+                            // @todo we are optimistic here, the library is assumed to define the struct
+                            parsed_args.push(Call::Library(Symbol {
+                                member: signature,
+                                ..storage_pointer.clone()
+                            }));
 
                             return parsed_args
                         }
@@ -1054,11 +2434,54 @@ impl<'a> Parser<'a> {
                 let parsed_function = self.parse_expression(function.as_ref());
 
                 parsed_args.extend(parsed_function);
+                // access checks of the bases which were not loaded, e.g. `_checkOwner()`
+                parsed_args.extend(self.parse_guard_call(function, args));
 
                 parsed_args
             }
             SolangExpression::NamedFunctionCall(_, expression, args) => {
-                // First we will handle case when we call a Library function of a storage pointer struct
+                let expression = match expression.as_ref() {
+                    SolangExpression::FunctionCallBlock(_, function, _) => function,
+                    _ => expression,
+                };
+
+                // @todo named arguments may be passed in any order, so only their count is reliable
+                let arg_types = args
+                    .iter()
+                    .map(|arg| self.parse_expression_type(&arg.expr))
+                    .collect::<Vec<_>>();
+
+                // First we will handle case when we call a function of this contract or its bases
+                if let Some(parsed_call) = self.parse_internal_call(expression, &arg_types) {
+                    let mut parsed_args = args
+                        .iter()
+                        .flat_map(|arg| self.parse_expression(&arg.expr))
+                        .collect::<Vec<_>>();
+                    parsed_args.push(parsed_call);
+                    return parsed_args
+                }
+
+                // Then we will handle case when we call a function attached with `using for`
+                if let Some(mut parsed_call) = self.parse_bound_call(expression, &arg_types) {
+                    parsed_call.extend(
+                        args.iter()
+                            .flat_map(|arg| self.parse_expression(&arg.expr))
+                            .collect::<Vec<_>>(),
+                    );
+                    return parsed_call
+                }
+
+                // Then we will handle case when we call a function of another contract
+                if let Some(mut parsed_call) = self.parse_external_call(expression, &arg_types) {
+                    parsed_call.extend(
+                        args.iter()
+                            .flat_map(|arg| self.parse_expression(&arg.expr))
+                            .collect::<Vec<_>>(),
+                    );
+                    return parsed_call
+                }
+
+                // Then we will handle case when we call a Library function of a storage pointer struct
 
                 if let SolangExpression::MemberAccess(_, left, right) = *expression.clone() {
                     // if on the left we have a variable definition
@@ -1067,9 +2490,7 @@ impl<'a> Parser<'a> {
                         let parsed_left = self.parse_identifier(&Some(left_ident.clone()));
 
                         // if on the left we have a storage pointer
-                        if let Some(storage_pointer) =
-                            self.local_storage_pointers.get(&parsed_left).cloned()
-                        {
+                        if let Some(storage_pointer) = self.local_storage_pointer(&parsed_left) {
                             // on the right side we have the function name
                             let parsed_right = self.parse_identifier(&Some(right.clone()));
 
@@ -1079,7 +2500,16 @@ impl<'a> Parser<'a> {
                                 .map(|arg| arg.expr.clone())
                                 .flat_map(|expression| self.parse_expression(&expression))
                                 .collect::<Vec<_>>();
-                            parsed_args.push(Call::Library(storage_pointer.clone(), parsed_right));
+                            let signature = self.find_pointer_function(
+                                &storage_pointer,
+                                &parsed_right,
+                                &arg_types,
+                            );
+                            // @todo we are optimistic here, the library is assumed to define the struct
+                            parsed_args.push(Call::Library(Symbol {
+                                member: signature,
+                                ..storage_pointer.clone()
+                            }));
 
                             return parsed_args
                         }
@@ -1102,8 +2532,7 @@ impl<'a> Parser<'a> {
                 let dos = boxed_expression!(parsed_if_true, if_true);
                 let cuatro = boxed_expression!(parsed_if_false, if_false);
 
-                uno.extend(dos);
-                uno.extend(cuatro);
+                uno.extend(Self::branches(vec![dos, cuatro]));
 
                 uno
             }
@@ -1118,25 +2547,20 @@ impl<'a> Parser<'a> {
             | SolangExpression::AssignMultiply(_, left, right)
             | SolangExpression::AssignDivide(_, left, right)
             | SolangExpression::AssignModulo(_, left, right) => {
-                let mut uno = boxed_expression!(parsed_condition, left);
-                let dos = boxed_expression!(parsed_if_true, right);
+                // the assigned value is evaluated before the storage is updated
+                let mut uno = boxed_expression!(parsed_if_true, right);
 
+                // storage pointers may be moved to other elements, e.g. `u = users[other]`,
+                // which does not write the storage
+                if let (SolangExpression::Assign(..), SolangExpression::Variable(identifier)) =
+                    (expression, left.as_ref())
+                {
+                    if self.realias_local(&identifier.name, right) {
+                        return uno
+                    }
+                }
                 // if left is a storage field we are updating storage
-                uno = uno
-                    .iter()
-                    .map(|call| {
-                        match call.clone() {
-                            Call::Read(call_type, contract, read) => {
-                                Call::Read(call_type, contract, read)
-                            }
-                            Call::ReadStorage(call_type, contract, read) => {
-                                Call::WriteStorage(call_type, contract, read)
-                            }
-                            Call::Write(..) | Call::WriteStorage(..) => call.clone(),
-                            _ => unreachable!("Should be unreachable"),
-                        }
-                    })
-                    .collect();
+                let dos = self.parse_assigned_expression(left);
 
                 uno.extend(dos);
 
@@ -1145,29 +2569,24 @@ impl<'a> Parser<'a> {
             SolangExpression::Variable(identifier) => {
                 let parsed_identifier = self.parse_identifier(&Some(identifier.clone()));
 
-                if let Some(member_type) = self.members_map.get(&parsed_identifier) {
+                if self.find_local(&parsed_identifier).is_some() {
+                    // local variables shadow the members
+                    Vec::default()
+                } else if let Some(member_type) = self.members_map.get(&parsed_identifier) {
                     match member_type {
-                        MemberType::StorageField(contract_name) => {
+                        MemberType::StorageField(symbol, _) => {
+                            vec![Call::ReadStorage(CallType::CallingStorage, symbol.clone())]
+                        }
+                        MemberType::Immutable(symbol) => {
                             vec![Call::ReadStorage(
-                                CallType::CallingStorage,
-                                contract_name.clone(),
-                                parsed_identifier,
+                                CallType::CallingImmutable,
+                                symbol.clone(),
                             )]
                         }
-                        MemberType::Function(function_header, contract_name) => {
-                            if function_header.view {
-                                vec![Call::Read(
-                                    CallType::CallingFunction,
-                                    contract_name.clone(),
-                                    parsed_identifier,
-                                )]
-                            } else {
-                                vec![Call::Write(
-                                    CallType::CallingFunction,
-                                    contract_name.clone(),
-                                    parsed_identifier,
-                                )]
-                            }
+                        // the function is not called here, so we can not tell the overloads apart
+                        MemberType::Function(overloads) => {
+                            let (function_header, symbol) = &overloads[0];
+                            vec![self.parse_function_access(function_header, symbol)]
                         }
                     }
                 } else {
@@ -1175,37 +2594,1374 @@ impl<'a> Parser<'a> {
                 }
             }
             SolangExpression::ArrayLiteral(_, content) => self.parse_expression_vec(content),
+            SolangExpression::List(_, parameters) => {
+                // tuples, e.g. `return (balance, total)`
+                parameters
+                    .iter()
+                    .filter_map(|(_, parameter)| parameter.as_ref())
+                    .filter(|parameter| parameter.name.is_none())
+                    .flat_map(|parameter| self.parse_expression(&parameter.ty))
+                    .collect()
+            }
             _ => Vec::default(),
         }
     }
 
-    /// Parses multiple Solang expression enum variants to Sol2Ink expression enum variants
+    /// Parses the location which is assigned to, deleted, incremented or decremented
     ///
-    /// `expressions` the original Solang expression enum variants
-    /// `location` the location where the expression is [being called](fn@parse_variable_access_location)
+    /// `expression` the assigned expression
     ///
-    /// Returns the vec of parsed `Expression` enum variant
-    fn parse_expression_vec(&mut self, expressions: &[SolangExpression]) -> Vec<Call> {
-        expressions
-            .iter()
-            .flat_map(|expression| self.parse_expression(expression))
-            .collect()
+    /// Only the assigned storage is written, storage used in subscripts is only read
+    fn parse_assigned_expression(&mut self, expression: &SolangExpression) -> Vec<Call> {
+        match expression {
+            SolangExpression::Parenthesis(_, expression) => {
+                self.parse_assigned_expression(expression)
+            }
+            SolangExpression::List(_, parameters) => {
+                // tuple destructuring, e.g. `(a, b) = f()` or `(uint256 a, ) = f()`
+                let mut calls = Vec::default();
+                for parameter in parameters
+                    .iter()
+                    .filter_map(|(_, parameter)| parameter.as_ref())
+                {
+                    match &parameter.name {
+                        Some(_) => self.declare_parameter(parameter),
+                        None => calls.extend(self.parse_assigned_expression(&parameter.ty)),
+                    }
+                }
+                calls
+            }
+            SolangExpression::Variable(identifier) => {
+                // the whole element the pointer is an alias of, e.g. `delete u`
+                match self.local_alias(&identifier.name) {
+                    Some(alias) if alias.path.is_empty() => {
+                        vec![Call::WriteStorage(alias.call_type, alias.storage)]
+                    }
+                    Some(alias) => {
+                        vec![Call::WriteStorageField(
+                            alias.call_type,
+                            alias.storage,
+                            alias.path.join("."),
+                        )]
+                    }
+                    None => Self::into_writes(self.parse_expression(expression)),
+                }
+            }
+            SolangExpression::ArraySubscript(_, array, index_maybe) => {
+                let mut calls = self.parse_assigned_expression(array);
+                if let Some(index) = index_maybe {
+                    calls.extend(self.parse_expression(index));
+                }
+                calls
+            }
+            SolangExpression::ArraySlice(_, array, left, right) => {
+                let mut calls = self.parse_assigned_expression(array);
+                for index in [left, right].into_iter().flatten() {
+                    calls.extend(self.parse_expression(index));
+                }
+                calls
+            }
+            SolangExpression::MemberAccess(_, left, right) => {
+                // we walk down to the root of `root[index].field_0.field_1`
+                let mut path = vec![right.name.clone()];
+                let mut indexes = Vec::default();
+                let mut root = left.as_ref();
+
+                loop {
+                    match root {
+                        SolangExpression::MemberAccess(_, inner, field) => {
+                            path.insert(0, field.name.clone());
+                            root = inner;
+                        }
+                        SolangExpression::ArraySubscript(_, inner, index_maybe) => {
+                            if let Some(index) = index_maybe {
+                                indexes.push(index.as_ref().clone());
+                            }
+                            root = inner;
+                        }
+                        SolangExpression::Parenthesis(_, inner) => root = inner,
+                        _ => break,
+                    }
+                }
+
+                let written = match root {
+                    SolangExpression::Variable(identifier) => {
+                        self.parse_assigned_path(&identifier.name, &path)
+                    }
+                    // fields of the pointer returned by a function, e.g. `s().balance += 1`
+                    SolangExpression::FunctionCall(..) => {
+                        self.returned_pointer_storage(root)
+                            .map(|storage| self.parse_pointer_write(&storage, &path))
+                    }
+                    _ => None,
+                };
+
+                if let Some(written) = written {
+                    let mut calls = vec![written];
+                    calls.extend(self.parse_expression_vec(&indexes));
+                    if let SolangExpression::FunctionCall(..) = root {
+                        calls.extend(self.parse_expression(root));
+                    }
+                    calls
+                } else {
+                    // we do not know where we write, so we consider all of the accessed storage written
+                    Self::into_writes(self.parse_expression(expression))
+                }
+            }
+            _ => Self::into_writes(self.parse_expression(expression)),
+        }
     }
 
-    /// Parses a Solang `IdentifierPath` struct to String
-    ///
-    /// `identifier_path` the original Solang identifier
+    /// Returns the write to the storage accessed by `root.path_0.path_1...`
     ///
-    /// Returns the parsed `String`
-    fn parse_identifier_path(&self, identifier_path: &IdentifierPath) -> String {
-        identifier_path
-            .identifiers
-            .iter()
-            .map(|identifier| identifier.name.clone())
-            .collect::<Vec<String>>()
+    /// `root` the name of the accessed variable
+    /// `path` the accessed fields of the variable
+    fn parse_assigned_path(&mut self, root: &str, path: &[String]) -> Option<Call> {
+        if let Some(alias) = self.local_alias(root) {
+            let mut path = path.to_vec();
+            path.splice(0..0, alias.path);
+
+            return Some(Call::WriteStorageField(
+                alias.call_type,
+                alias.storage,
+                path.join("."),
+            ))
+        }
+
+        if self.local_storage_pointer(root).is_some() {
+            let storage = self.pointer_storage(root)?;
+
+            return Some(self.parse_pointer_write(&storage, path))
+        }
+
+        if self.find_local(root).is_some() {
+            // local variables are not stored
+            return None
+        }
+
+        if let Some(MemberType::StorageField(symbol, _)) = self.members_map.get(root) {
+            return Some(Call::WriteStorageField(
+                CallType::CallingStorage,
+                symbol.clone(),
+                path.join("."),
+            ))
+        }
+
+        None
+    }
+
+    /// Returns the access checks of a condition on the caller, e.g. the guard of `owner` for
+    /// `msg.sender == owner` or of `MINTER_ROLE` for `hasRole(MINTER_ROLE, msg.sender)`
+    ///
+    /// Only the conditions which pass for the required account grant the access, so
+    /// `msg.sender != owner` requires the owner only if it is negated, e.g. by
+    /// `if (msg.sender != owner) revert ...`, checks of `tx.origin` are marked on their own
+    ///
+    /// `condition` the checked condition
+    /// `negated` the condition must fail to continue, e.g. the condition of `if (...) revert`
+    fn parse_guard(&self, condition: &SolangExpression, negated: bool) -> Vec<Call> {
+        match condition {
+            SolangExpression::Not(_, expression) => self.parse_guard(expression, !negated),
+            SolangExpression::Parenthesis(_, expression) => self.parse_guard(expression, negated),
+            // each side may be the check letting the caller through
+            SolangExpression::And(_, left, right) | SolangExpression::Or(_, left, right) => {
+                let mut guards = self.parse_guard(left, negated);
+                guards.extend(self.parse_guard(right, negated));
+                guards
+            }
+            SolangExpression::Equal(_, left, right) => self.parse_comparison(left, right, !negated),
+            SolangExpression::NotEqual(_, left, right) => {
+                self.parse_comparison(left, right, negated)
+            }
+            // e.g. `admins[msg.sender]`
+            SolangExpression::ArraySubscript(_, mapping, Some(key)) => {
+                if self.is_origin(key) {
+                    vec![Call::OriginCheck]
+                } else if self.is_sender(key) && !negated {
+                    vec![Call::Guard(self.expression_text(mapping))]
+                } else {
+                    Vec::default()
+                }
+            }
+            // e.g. `hasRole(MINTER_ROLE, msg.sender)` or `isOperator(msg.sender)`
+            SolangExpression::FunctionCall(_, function, args) => {
+                if args.iter().any(|arg| self.is_origin(arg)) {
+                    return vec![Call::OriginCheck]
+                }
+                if negated {
+                    return Vec::default()
+                }
+
+                let function_text = self.expression_text(function);
+                let name = function_text.rsplit('.').next().unwrap_or_default();
+                let is_predicate = name
+                    .trim_start_matches('_')
+                    .strip_prefix("is")
+                    .and_then(|rest| rest.chars().next())
+                    .is_some_and(|first| first.is_ascii_uppercase());
+
+                match args.as_slice() {
+                    [role, account] if name == "hasRole" && self.is_sender(account) => {
+                        vec![Call::Guard(self.expression_text(role))]
+                    }
+                    _ if is_predicate && args.iter().any(|arg| self.is_sender(arg)) => {
+                        vec![Call::Guard(function_text)]
+                    }
+                    _ => Vec::default(),
+                }
+            }
+            _ => Vec::default(),
+        }
+    }
+
+    /// Returns the access check of a comparison of the caller, e.g. `msg.sender == owner`
+    ///
+    /// Comparisons with literals, e.g. `msg.sender != address(0)`, do not require any account
+    ///
+    /// `left` the left side of the comparison
+    /// `right` the right side of the comparison
+    /// `grants` the comparison passes for the compared account
+    fn parse_comparison(
+        &self,
+        left: &SolangExpression,
+        right: &SolangExpression,
+        grants: bool,
+    ) -> Vec<Call> {
+        if self.is_origin(left) || self.is_origin(right) {
+            return vec![Call::OriginCheck]
+        }
+        if !grants {
+            return Vec::default()
+        }
+
+        let account = match (self.is_sender(left), self.is_sender(right)) {
+            (true, false) => right,
+            (false, true) => left,
+            _ => return Vec::default(),
+        };
+        if Self::is_literal(account) {
+            return Vec::default()
+        }
+
+        vec![Call::Guard(self.expression_text(account))]
+    }
+
+    /// Returns the access check made by calling a function of the access control contracts,
+    /// e.g. the guard of `MINTER_ROLE` for `_checkRole(MINTER_ROLE)`
+    ///
+    /// `function` the called expression
+    /// `args` the arguments of the call
+    fn parse_guard_call(
+        &self,
+        function: &SolangExpression,
+        args: &[SolangExpression],
+    ) -> Option<Call> {
+        let SolangExpression::Variable(identifier) = function else {
+            return None
+        };
+
+        match (identifier.name.as_str(), args) {
+            ("_checkRole", [role, ..]) => Some(Call::Guard(self.expression_text(role))),
+            ("_checkOwner", []) => Some(Call::Guard(String::from("owner()"))),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the expression is a literal, e.g. `address(0)` or `0xdead`
+    ///
+    /// `expression` the Solang expression
+    fn is_literal(expression: &SolangExpression) -> bool {
+        match expression {
+            SolangExpression::BoolLiteral(..)
+            | SolangExpression::NumberLiteral(..)
+            | SolangExpression::HexNumberLiteral(..)
+            | SolangExpression::AddressLiteral(..)
+            | SolangExpression::StringLiteral(..)
+            | SolangExpression::HexLiteral(..) => true,
+            // conversions of literals, e.g. `address(0)`
+            SolangExpression::FunctionCall(_, function, args) => {
+                matches!(function.as_ref(), SolangExpression::Type(..))
+                    && matches!(args.as_slice(), [arg] if Self::is_literal(arg))
+            }
+            SolangExpression::Parenthesis(_, expression) => Self::is_literal(expression),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the expression is the caller, e.g. `msg.sender`
+    ///
+    /// `expression` the Solang expression
+    fn is_sender(&self, expression: &SolangExpression) -> bool {
+        matches!(
+            self.expression_text(expression).as_str(),
+            "msg.sender" | "_msgSender()"
+        )
+    }
+
+    /// Returns `true` if the expression is the origin of the transaction, `tx.origin`
+    ///
+    /// `expression` the Solang expression
+    fn is_origin(&self, expression: &SolangExpression) -> bool {
+        self.expression_text(expression) == "tx.origin"
+    }
+
+    /// Returns `true` if the options of a call contain the option, e.g. `value` of `{value: amount}`
+    ///
+    /// `block` the options of the call
+    /// `option` the name of the option
+    fn has_call_option(block: &SolangStatement, option: &str) -> bool {
+        matches!(block, SolangStatement::Args(_, args) if args.iter().any(|arg| arg.name.name == option))
+    }
+
+    /// Parses the creation of a contract, e.g. `new Pool(token)` or `new Pool{salt: salt}(token)`
+    ///
+    /// `expression` the operand of `new`
+    ///
+    /// Returns `None` if no contract is created, e.g. `new uint256[](length)`
+    fn parse_creation(expression: &SolangExpression) -> Option<Call> {
+        let SolangExpression::FunctionCall(_, contract, _) = expression else {
+            return None
+        };
+        // contracts created with a salt are deployed by `create2`
+        let (contract, method) = match contract.as_ref() {
+            SolangExpression::FunctionCallBlock(_, contract, block)
+                if Self::has_call_option(block, "salt") =>
+            {
+                (contract, "create2")
+            }
+            SolangExpression::FunctionCallBlock(_, contract, _) => (contract, "create"),
+            _ => (contract, "create"),
+        };
+        let SolangExpression::Variable(identifier) = contract.as_ref() else {
+            return None
+        };
+
+        Some(Call::Create(
+            Some(identifier.name.clone()),
+            String::from(method),
+        ))
+    }
+
+    /// Returns the recipient of the ETH sent to an address, e.g. a parameter `to` of `to.transfer(amount)`
+    ///
+    /// `target` the expression of the receiving address
+    fn parse_recipient(&self, target: &SolangExpression) -> Recipient {
+        match target {
+            // conversions, e.g. `payable(to)` or `IVault(vault)`, do not change the address
+            SolangExpression::FunctionCall(_, function, args) if args.len() == 1 => {
+                let is_conversion = match function.as_ref() {
+                    SolangExpression::Type(..) => true,
+                    SolangExpression::Variable(identifier) => {
+                        self.contract_definition(&identifier.name).is_some()
+                    }
+                    _ => false,
+                };
+                if is_conversion {
+                    return self.parse_recipient(&args[0])
+                }
+            }
+            SolangExpression::Parenthesis(_, expression) => return self.parse_recipient(expression),
+            _ => (),
+        }
+
+        if self.is_sender(target) {
+            return Recipient::Sender
+        }
+        if let SolangExpression::Variable(identifier) = target {
+            match self.find_local(&identifier.name) {
+                Some(local) if local.parameter => {
+                    return Recipient::Parameter(identifier.name.clone())
+                }
+                Some(_) => (),
+                None => {
+                    if let Some(MemberType::StorageField(..) | MemberType::Immutable(..)) =
+                        self.members_map.get(&identifier.name)
+                    {
+                        return Recipient::Storage(identifier.name.clone())
+                    }
+                }
+            }
+        }
+
+        Recipient::Other(self.expression_text(target))
+    }
+
+    /// Returns `true` if the statement only reverts, e.g. `revert Unauthorized()`
+    ///
+    /// `statement` the Solang statement
+    fn reverts(statement: &SolangStatement) -> bool {
+        match statement {
+            SolangStatement::Revert(..) | SolangStatement::RevertNamedArgs(..) => true,
+            SolangStatement::Block { statements, .. } => {
+                matches!(statements.as_slice(), [statement] if Self::reverts(statement))
+            }
+            SolangStatement::Expression(_, SolangExpression::FunctionCall(_, function, _)) => {
+                matches!(function.as_ref(), SolangExpression::Variable(identifier) if identifier.name == "revert")
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the expression as it is written, e.g. `MINTER_ROLE` or `keccak256("MINTER")`
+    ///
+    /// `expression` the Solang expression
+    fn expression_text(&self, expression: &SolangExpression) -> String {
+        let text = |expression: &SolangExpression| self.expression_text(expression);
+        let binary = |left: &SolangExpression, operator: &str, right: &SolangExpression| {
+            format!("{} {operator} {}", text(left), text(right))
+        };
+
+        match expression {
+            SolangExpression::Variable(identifier) => identifier.name.clone(),
+            SolangExpression::MemberAccess(_, left, right) => {
+                format!("{}.{}", text(left), right.name)
+            }
+            SolangExpression::FunctionCall(_, function, args) => {
+                format!(
+                    "{}({})",
+                    text(function),
+                    args.iter().map(text).collect::<Vec<_>>().join(", ")
+                )
+            }
+            SolangExpression::ArraySubscript(_, array, index) => {
+                format!(
+                    "{}[{}]",
+                    text(array),
+                    index.as_ref().map(|index| text(index)).unwrap_or_default()
+                )
+            }
+            SolangExpression::Parenthesis(_, expression) => format!("({})", text(expression)),
+            SolangExpression::Not(_, expression) => format!("!{}", text(expression)),
+            SolangExpression::Equal(_, left, right) => binary(left, "==", right),
+            SolangExpression::NotEqual(_, left, right) => binary(left, "!=", right),
+            SolangExpression::And(_, left, right) => binary(left, "&&", right),
+            SolangExpression::Or(_, left, right) => binary(left, "||", right),
+            SolangExpression::BoolLiteral(_, value) => value.to_string(),
+            SolangExpression::NumberLiteral(_, number, exponent, _) if exponent.is_empty() => {
+                number.clone()
+            }
+            SolangExpression::NumberLiteral(_, number, exponent, _) => {
+                format!("{number}e{exponent}")
+            }
+            SolangExpression::HexNumberLiteral(_, number, _)
+            | SolangExpression::AddressLiteral(_, number) => number.clone(),
+            SolangExpression::StringLiteral(literals) => {
+                format!(
+                    "\"{}\"",
+                    literals
+                        .iter()
+                        .map(|literal| literal.string.clone())
+                        .collect::<String>()
+                )
+            }
+            SolangExpression::Type(..) => self.parse_type(expression),
+            SolangExpression::This(_) => String::from("this"),
+            _ => String::from("..."),
+        }
+    }
+
+    /// Returns the calls of alternative paths of the control flow, e.g. `if` and `else`,
+    /// enclosed in the flow markers
+    ///
+    /// `paths` the calls of each of the paths
+    fn branches(mut paths: Vec<Vec<Call>>) -> Vec<Call> {
+        // a path which is not written, e.g. a missing `else`, does not call anything
+        paths.resize(paths.len().max(2), Vec::default());
+
+        let mut calls = vec![Call::Branch];
+        for (index, path) in paths.into_iter().enumerate() {
+            if index > 0 {
+                calls.push(Call::Path);
+            }
+            calls.extend(path);
+        }
+        calls.push(Call::Merge);
+        calls
+    }
+
+    /// Returns the calls of a loop enclosed in the flow markers, they may be repeated
+    ///
+    /// `body` the calls of one iteration
+    fn repeated(body: Vec<Call>) -> Vec<Call> {
+        let mut calls = vec![Call::Loop];
+        calls.extend(body);
+        calls.push(Call::EndLoop);
+        calls
+    }
+
+    /// Returns the write to the storage accessed through a storage pointer by
+    /// `pointer.path_0.path_1...`
+    ///
+    /// `storage` the storage the pointer points into
+    /// `path` the accessed fields of the pointer
+    fn parse_pointer_write(&mut self, storage: &Symbol, path: &[String]) -> Call {
+        self.record_storage_access(storage, &path[0]);
+
+        if path.len() == 1 {
+            Call::WriteStorage(CallType::CallingStoragePointer, storage.field(&path[0]))
+        } else {
+            Call::WriteStorageField(
+                CallType::CallingStoragePointer,
+                storage.field(&path[0]),
+                path[1..].join("."),
+            )
+        }
+    }
+
+    /// Parses a call of a member mutating a storage array or bytes, e.g. `queue.push(item)`,
+    /// `users[id].positions.pop()` or `data.push()`
+    ///
+    /// `function` the called expression
+    ///
+    /// Returns the write of the mutated storage, or `None` if the function is not `push` or `pop`
+    fn parse_array_mutation(&mut self, function: &SolangExpression) -> Option<Vec<Call>> {
+        let SolangExpression::MemberAccess(_, array, member) = function else {
+            return None
+        };
+        if member.name != "push" && member.name != "pop" {
+            return None
+        }
+
+        // struct pointers may have library functions named the same
+        if let SolangExpression::Variable(identifier) = array.as_ref() {
+            if self.local_storage_pointer(&identifier.name).is_some() {
+                return None
+            }
+        }
+
+        Some(self.parse_assigned_expression(array))
+    }
+
+    /// Turns the storage reads into storage writes
+    ///
+    /// `calls` the calls made while evaluating the written expression
+    fn into_writes(calls: Vec<Call>) -> Vec<Call> {
+        calls
+            .into_iter()
+            .map(|call| {
+                match call {
+                    Call::ReadStorage(call_type, symbol) => Call::WriteStorage(call_type, symbol),
+                    _ => call,
+                }
+            })
+            .collect()
+    }
+
+    /// Remembers that a field of a struct was accessed through a storage pointer
+    ///
+    /// `storage_pointer` the struct accessed through the storage pointer
+    /// `field` the accessed field
+    fn record_storage_access(&mut self, storage_pointer: &Symbol, field: &str) {
+        let current_storage = self
+            .storage_access
+            .entry(storage_pointer.clone())
+            .or_default();
+
+        if !current_storage.iter().any(|current| current == field) {
+            current_storage.insert(0, field.to_string());
+        }
+    }
+
+    /// Parses a call of a function of this contract or of its bases, e.g. `_mint(to, id)`,
+    /// `super._update(from, to)` or `ERC20._update(from, to)`, or of a library called by its
+    /// name, e.g. `LibDiamond.setContractOwner(owner)`
+    ///
+    /// `function` the called expression
+    /// `arg_types` the types of the arguments
+    ///
+    /// Returns the call of the overload matching the arguments, or `None` if the function
+    /// is not a member of the contract or of a library
+    fn parse_internal_call(
+        &mut self,
+        function: &SolangExpression,
+        arg_types: &[Option<String>],
+    ) -> Option<Call> {
+        match function {
+            SolangExpression::Variable(identifier) => {
+                if self.find_local(&identifier.name).is_some() {
+                    return None
+                }
+                let Some(MemberType::Function(overloads)) = self.members_map.get(&identifier.name)
+                else {
+                    return None
+                };
+                let (function_header, symbol) = Self::select_overload(overloads, Some(arg_types))?;
+
+                Some(self.parse_function_access(&function_header, &symbol))
+            }
+            SolangExpression::MemberAccess(_, left, right) => {
+                let SolangExpression::Variable(left_ident) = left.as_ref() else {
+                    return None
+                };
+
+                if let Some(call) =
+                    self.parse_base_access(&left_ident.name, &right.name, Some(arg_types))
+                {
+                    return Some(call)
+                }
+
+                if self.find_local(&left_ident.name).is_some()
+                    || self.members_map.contains_key(&left_ident.name)
+                    || !matches!(
+                        self.contract_definition(&left_ident.name)
+                            .map(|definition| &definition.ty),
+                        Some(ContractTy::Library(_))
+                    )
+                {
+                    return None
+                }
+
+                let (_, symbol) = Self::select_overload(
+                    &self.find_overloads(&left_ident.name, &right.name),
+                    Some(arg_types),
+                )?;
+
+                Some(Call::Library(symbol))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses an access of a member of a base, e.g. `super._update` or `ERC20._update`
+    ///
+    /// Calls of implementations in the bases are resolved once we know the linearization
+    ///
+    /// `left` the name of the base or `super`
+    /// `member_name` the name of the accessed member
+    /// `arg_types` the types of the arguments, `None` if the member is not called
+    ///
+    /// Returns `Super` or `Base` call of the member, or `None` if `left` is not a base or the
+    /// member is not a function of the base
+    fn parse_base_access(
+        &self,
+        left: &str,
+        member_name: &str,
+        arg_types: Option<&[Option<String>]>,
+    ) -> Option<Call> {
+        // overrides share the signature, so the overload known to this contract is the called one
+        let member = match self.members_map.get(member_name) {
+            Some(MemberType::Function(overloads)) => {
+                Self::select_overload(overloads, arg_types)
+                    .map(|(function_header, _)| function_header.signature())
+            }
+            _ => None,
+        }
+        .unwrap_or(member_name.to_string());
+
+        if left == "super" {
+            return Some(Call::Super(self.symbol(&self.current_contract, &member)))
+        }
+        if self.find_local(left).is_none()
+            && !self.members_map.contains_key(left)
+            && matches!(
+                self.contract_definition(left)
+                    .map(|definition| &definition.ty),
+                Some(ContractTy::Contract(_)) | Some(ContractTy::Abstract(_))
+            )
+        {
+            // enums, structs and constants of the base are not resolved along the linearization
+            if self.find_overloads(left, member_name).is_empty() {
+                return None
+            }
+            return Some(Call::Base(self.symbol(left, &member)))
+        }
+
+        None
+    }
+
+    /// Returns the overload of a function called with the arguments
+    ///
+    /// Overloads are matched by the count of the arguments first, then by the known types
+    /// of the arguments, we are optimistic and return the first overload if none matches
+    ///
+    /// `overloads` the overloads of the function
+    /// `arg_types` the types of the arguments, `None` if the function is not called
+    fn select_overload(
+        overloads: &[(FunctionHeader, Symbol)],
+        arg_types: Option<&[Option<String>]>,
+    ) -> Option<(FunctionHeader, Symbol)> {
+        let mut selected = overloads.first()?;
+
+        if let Some(arg_types) = arg_types {
+            let mut best_matches = None;
+
+            for overload in overloads
+                .iter()
+                .filter(|(function_header, _)| function_header.params.len() == arg_types.len())
+            {
+                let matches = overload
+                    .0
+                    .params
+                    .iter()
+                    .zip(arg_types.iter())
+                    .filter(|(param, arg_type)| {
+                        arg_type
+                            .as_ref()
+                            .is_some_and(|arg_type| Self::is_same_type(param, arg_type))
+                    })
+                    .count();
+
+                if best_matches.is_none_or(|best_matches| matches > best_matches) {
+                    best_matches = Some(matches);
+                    selected = overload;
+                }
+            }
+        }
+
+        Some(selected.clone())
+    }
+
+    /// Returns the overloads of a function defined by a contract or by its bases
+    ///
+    /// `contract` the name of the contract
+    /// `function_name` the name of the function
+    fn find_overloads(&self, contract: &str, function_name: &str) -> Vec<(FunctionHeader, Symbol)> {
+        // the most base contract first, like the merge of the bases
+        let mut contracts = self.linearize_visible(contract);
+        contracts.reverse();
+
+        let mut overloads = Vec::default();
+        for (source_unit, contract) in contracts {
+            let Some(contract_definition) = self
+                .contract_definitions
+                .get(&(source_unit.clone(), contract.clone()))
+            else {
+                continue
+            };
+
+            for part in contract_definition.parts.iter() {
+                if let ContractPart::FunctionDefinition(function_definition) = part {
+                    if function_definition
+                        .name
+                        .as_ref()
+                        .is_some_and(|name| name.name == function_name)
+                    {
+                        let view = function_definition.attributes.iter().any(|attribute| {
+                            matches!(
+                                attribute,
+                                FunctionAttribute::Mutability(Mutability::Pure(_))
+                                    | FunctionAttribute::Mutability(Mutability::View(_))
+                            )
+                        });
+                        let function_header = FunctionHeader {
+                            name: function_name.to_string(),
+                            params: self.parse_params(function_definition),
+                            view,
+                            ..Default::default()
+                        };
+                        let symbol =
+                            Symbol::new(&source_unit, &contract, &function_header.signature());
+                        overloads.push((function_header, symbol));
+                    }
+                }
+            }
+        }
+
+        overloads
+    }
+
+    /// Returns `true` if a contract or its bases declare a storage field with the name, public
+    /// fields are read by their getters
+    ///
+    /// `contract` the name of the contract
+    /// `field_name` the name of the field
+    fn has_field(&self, contract: &str, field_name: &str) -> bool {
+        self.linearize_visible(contract)
+            .iter()
+            .filter_map(|contract| self.contract_definitions.get(contract))
+            .flat_map(|contract_definition| contract_definition.parts.iter())
+            .any(|part| {
+                matches!(
+                    part,
+                    ContractPart::VariableDefinition(variable_definition)
+                        if variable_definition
+                            .name
+                            .as_ref()
+                            .is_some_and(|name| name.name == field_name)
+                )
+            })
+    }
+
+    /// Parses a call of a function of another contract, e.g. `IERC20(token).transfer(to, amount)`
+    ///
+    /// `function` the called expression
+    /// `arg_types` the types of the arguments
+    ///
+    /// Returns the calls made while evaluating the receiver together with the `External` call,
+    /// or `None` if the receiver is not typed by a contract or an interface
+    fn parse_external_call(
+        &mut self,
+        function: &SolangExpression,
+        arg_types: &[Option<String>],
+    ) -> Option<Vec<Call>> {
+        if let SolangExpression::MemberAccess(_, left, right) = function {
+            let receiver_type = self.parse_receiver_type(left)?;
+            let parsed_right = self.parse_identifier(&Some(right.clone()));
+
+            // getters of public storage fields are not declared as functions
+            let (member, view) = match Self::select_overload(
+                &self.find_overloads(&receiver_type, &parsed_right),
+                Some(arg_types),
+            ) {
+                Some((function_header, _)) => (function_header.signature(), function_header.view),
+                None => {
+                    let getter = self.has_field(&receiver_type, &parsed_right);
+                    (parsed_right, getter)
+                }
+            };
+
+            let mut parsed_receiver = self.parse_expression(left);
+            parsed_receiver.push(Call::External(self.symbol(&receiver_type, &member), view));
+
+            return Some(parsed_receiver)
+        }
+        None
+    }
+
+    /// Parses a call of a function attached to the type of the receiver with `using for`,
+    /// e.g. `_holders.add(holder)`
+    ///
+    /// `function` the called expression
+    /// `arg_types` the types of the arguments
+    ///
+    /// Returns the access of the receiver together with the `Library` call, the receiver is
+    /// considered written if the attached function takes it as a `storage` parameter and is
+    /// not `view` or `pure`, or `None` if no function is attached to the type of the receiver
+    fn parse_bound_call(
+        &mut self,
+        function: &SolangExpression,
+        arg_types: &[Option<String>],
+    ) -> Option<Vec<Call>> {
+        let SolangExpression::MemberAccess(_, receiver, right) = function else {
+            return None
+        };
+
+        let receiver_type = self.parse_expression_type(receiver)?;
+        let (bound_function, writes_receiver) =
+            self.find_bound_function(&receiver_type, &right.name, arg_types)?;
+
+        let mut parsed_receiver = if writes_receiver {
+            self.parse_assigned_expression(receiver)
+        } else {
+            self.parse_expression(receiver)
+        };
+        parsed_receiver.push(Call::Library(bound_function));
+
+        Some(parsed_receiver)
+    }
+
+    /// Returns the function attached to a type by the `using for` directives in effect
+    ///
+    /// Directives of the current contract, of the current file and the global ones are in effect
+    ///
+    /// `ty` the type of the receiver
+    /// `function_name` the name of the called function
+    /// `arg_types` the types of the arguments, without the receiver
+    ///
+    /// Returns the attached function and whether it writes to the receiver
+    fn find_bound_function(
+        &self,
+        ty: &str,
+        function_name: &str,
+        arg_types: &[Option<String>],
+    ) -> Option<(Symbol, bool)> {
+        let file_scope = Self::file_scope(&self.current_source_unit);
+
+        // the receiver is passed as the first argument
+        let mut bound_arg_types = vec![Some(ty.to_string())];
+        bound_arg_types.extend(arg_types.iter().cloned());
+
+        let mut using_directives = Vec::default();
+        for (scope, directives) in self.using_directives.iter() {
+            using_directives.extend(directives.iter().filter(|directive| {
+                directive.global || scope == &self.current_contract || scope == &file_scope
+            }));
+        }
+
+        for directive in using_directives {
+            if let Some(directive_type) = &directive.ty {
+                if !Self::is_same_type(directive_type, ty) {
+                    continue
+                }
+            }
+
+            if let Some(library) = &directive.library {
+                if let Some(library_function) =
+                    self.find_library_function(library, function_name, &bound_arg_types)
+                {
+                    return Some(library_function)
+                }
+            }
+
+            for function in directive.functions.iter() {
+                match function.rsplit_once('.') {
+                    Some((library, name)) if name == function_name => {
+                        if let Some(library_function) =
+                            self.find_library_function(library, function_name, &bound_arg_types)
+                        {
+                            return Some(library_function)
+                        }
+                    }
+                    None if function == function_name => {
+                        if let Some(MemberType::Function(overloads)) =
+                            self.free_functions.get(function_name)
+                        {
+                            let (function_header, symbol) =
+                                Self::select_overload(overloads, Some(&bound_arg_types))?;
+                            // @todo we do not know the location of the parameter here
+                            return Some((symbol, !function_header.view))
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns the function of a library called with the arguments and whether it writes
+    /// to its first parameter, if the library defines the function
+    ///
+    /// `library` the name of the library
+    /// `function_name` the name of the function
+    /// `arg_types` the types of the arguments
+    fn find_library_function(
+        &self,
+        library: &str,
+        function_name: &str,
+        arg_types: &[Option<String>],
+    ) -> Option<(Symbol, bool)> {
+        let (function_header, symbol) = Self::select_overload(
+            &self.find_overloads(library, function_name),
+            Some(arg_types),
+        )?;
+
+        let storage_receiver = self
+            .contract_definitions
+            .get(&(symbol.source_unit.clone(), symbol.contract.clone()))?
+            .parts
+            .iter()
+            .find_map(|part| {
+                match part {
+                    ContractPart::FunctionDefinition(function_definition)
+                        if function_definition
+                            .name
+                            .as_ref()
+                            .is_some_and(|name| name.name == function_name)
+                            && self.parse_params(function_definition) == function_header.params =>
+                    {
+                        Some(
+                            function_definition
+                                .params
+                                .first()
+                                .and_then(|(_, param)| param.as_ref())
+                                .is_some_and(|param| {
+                                    matches!(param.storage, Some(StorageLocation::Storage(_)))
+                                }),
+                        )
+                    }
+                    _ => None,
+                }
+            })?;
+
+        Some((symbol, !function_header.view && storage_receiver))
+    }
+
+    /// Returns the signature of the library function called on a storage pointer,
+    /// e.g. `add(Set,bytes32)` for `set.add(value)`
+    ///
+    /// `storage_pointer` the struct the storage pointer points to
+    /// `function_name` the name of the called function
+    /// `arg_types` the types of the arguments, without the receiver
+    fn find_pointer_function(
+        &self,
+        storage_pointer: &Symbol,
+        function_name: &str,
+        arg_types: &[Option<String>],
+    ) -> String {
+        let mut bound_arg_types = vec![Some(format!(
+            "{}.{}",
+            storage_pointer.contract, storage_pointer.member
+        ))];
+        bound_arg_types.extend(arg_types.iter().cloned());
+
+        self.find_library_function(&storage_pointer.contract, function_name, &bound_arg_types)
+            .map(|(symbol, _)| symbol.member)
+            .unwrap_or(function_name.to_string())
+    }
+
+    /// Returns true if the types are the same, qualified and unqualified names of a type match
+    fn is_same_type(a: &str, b: &str) -> bool {
+        let unqualified = |ty: &str| ty.rsplit('.').next().unwrap_or(ty).to_string();
+        a == b || unqualified(a) == unqualified(b)
+    }
+
+    /// Returns the static type of an expression if we know it, e.g. `EnumerableSet.AddressSet`
+    ///
+    /// `expression` the expression
+    fn parse_expression_type(&self, expression: &SolangExpression) -> Option<String> {
+        // members of the global variables, e.g. `msg.sender`
+        let global_type = match self.expression_text(expression).as_str() {
+            "msg.sender" | "tx.origin" | "block.coinbase" => Some("address"),
+            "msg.value" | "block.timestamp" | "block.number" | "block.chainid"
+            | "block.basefee" => Some("uint256"),
+            "msg.data" => Some("bytes"),
+            _ => None,
+        };
+        if let Some(global_type) = global_type {
+            return Some(global_type.to_string())
+        }
+
+        match expression {
+            SolangExpression::Parenthesis(_, expression) => self.parse_expression_type(expression),
+            SolangExpression::BoolLiteral(..) => Some(String::from("bool")),
+            SolangExpression::This(_) => Some(self.current_contract.clone()),
+            SolangExpression::Variable(identifier) => {
+                if let Some(local_variable) = self.find_local(&identifier.name) {
+                    match &local_variable.storage_pointer {
+                        Some(storage_pointer) => {
+                            Some(format!(
+                                "{}.{}",
+                                storage_pointer.contract, storage_pointer.member
+                            ))
+                        }
+                        None => local_variable.ty.clone(),
+                    }
+                } else if let Some(MemberType::StorageField(_, field_type)) =
+                    self.members_map.get(&identifier.name)
+                {
+                    Some(field_type.clone())
+                } else {
+                    None
+                }
+            }
+            SolangExpression::ArraySubscript(_, array, _) => {
+                Self::element_type(&self.parse_expression_type(array)?)
+            }
+            SolangExpression::MemberAccess(_, left, field) => {
+                let struct_type = self.parse_expression_type(left)?;
+                let struct_symbol = match struct_type.rsplit_once('.') {
+                    Some((contract, name)) => self.symbol(contract, name),
+                    None => self.resolve_struct(&struct_type),
+                };
+
+                self.structs
+                    .get(&struct_symbol)?
+                    .fields
+                    .iter()
+                    .find(|declaration| {
+                        declaration
+                            .name
+                            .as_ref()
+                            .is_some_and(|name| name.name == field.name)
+                    })
+                    .map(|declaration| self.parse_type(&declaration.ty))
+            }
+            SolangExpression::FunctionCall(_, function, args) => {
+                match function.as_ref() {
+                    // explicit conversion, e.g. `IERC20(token)` or `address(this)`
+                    SolangExpression::Type(..) if args.len() == 1 => {
+                        Some(self.parse_type(function))
+                    }
+                    SolangExpression::Variable(identifier)
+                        if args.len() == 1
+                            && !self.members_map.contains_key(&identifier.name)
+                            && !self.free_functions.contains_key(&identifier.name) =>
+                    {
+                        Some(self.parse_type(function))
+                    }
+                    // value returned by a function, e.g. `getVault()` or `registry.vault(id)`
+                    SolangExpression::Variable(identifier) => {
+                        self.parse_return_type(&self.current_contract, &identifier.name, args.len())
+                    }
+                    SolangExpression::MemberAccess(_, receiver, member) => {
+                        let contract = self.parse_receiver_type(receiver)?;
+                        self.parse_return_type(&contract, &member.name, args.len())
+                    }
+                    _ => None,
+                }
+            }
+            SolangExpression::Type(..) => Some(self.parse_type(expression)),
+            _ => None,
+        }
+    }
+
+    /// Returns the type of the elements of an array or of the values of a mapping
+    ///
+    /// `ty` the type of the array or the mapping, e.g. `IVault[]` or `mapping(uint256 => IVault)`
+    fn element_type(ty: &str) -> Option<String> {
+        if let Some(element_type) = ty.strip_suffix("[]") {
+            Some(element_type.to_string())
+        } else {
+            // `mapping(key => value)`
+            ty.strip_prefix("mapping(")?
+                .strip_suffix(')')?
+                .split_once(" => ")
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    /// Returns the type of the first value returned by a function of a contract or its bases,
+    /// getters of public storage fields return the values of their fields
+    ///
+    /// `contract` the name of the contract
+    /// `function_name` the name of the function
+    /// `args` the count of the arguments
+    fn parse_return_type(
+        &self,
+        contract: &str,
+        function_name: &str,
+        args: usize,
+    ) -> Option<String> {
+        let parts = self
+            .linearize_visible(contract)
+            .into_iter()
+            .filter_map(|contract| self.contract_definitions.get(&contract))
+            .flat_map(|contract_definition| contract_definition.parts.iter())
+            .collect::<Vec<_>>();
+        let is_named = |name: &Option<Identifier>| {
+            name.as_ref().is_some_and(|name| name.name == function_name)
+        };
+
+        for part in parts {
+            match part {
+                ContractPart::FunctionDefinition(function_definition)
+                    if is_named(&function_definition.name)
+                        && function_definition.params.len() == args =>
+                {
+                    return function_definition
+                        .returns
+                        .first()
+                        .and_then(|(_, parameter)| parameter.as_ref())
+                        .map(|parameter| self.parse_type(&parameter.ty))
+                }
+                ContractPart::VariableDefinition(variable_definition)
+                    if is_named(&variable_definition.name) =>
+                {
+                    // each argument indexes the mapping or the array
+                    let mut ty = self.parse_type(&variable_definition.ty);
+                    for _ in 0..args {
+                        ty = Self::element_type(&ty)?;
+                    }
+                    return Some(ty)
+                }
+                _ => (),
+            }
+        }
+
+        None
+    }
+
+    /// Parses a Solang `using for` directive
+    ///
+    /// `using` the Solang using directive
+    fn parse_using(&self, using: &Using) -> UsingDirective {
+        let (library, functions) = match &using.list {
+            UsingList::Library(library) => {
+                (Some(self.parse_identifier_path(library)), Vec::default())
+            }
+            UsingList::Functions(functions) => {
+                (
+                    None,
+                    functions
+                        .iter()
+                        .map(|function| self.parse_identifier_path(&function.path))
+                        .collect(),
+                )
+            }
+            UsingList::Error => (None, Vec::default()),
+        };
+
+        UsingDirective {
+            library,
+            functions,
+            ty: using.ty.as_ref().map(|ty| self.parse_type(ty)),
+            global: using.global.is_some(),
+        }
+    }
+
+    /// Returns the static type of a receiver of a call if it is a contract or an interface
+    ///
+    /// `expression` the receiver of the call
+    fn parse_receiver_type(&self, expression: &SolangExpression) -> Option<String> {
+        // e.g. `IERC20(token)`, `vaults[i]`, `s.token`, `getVault()` or `this`
+        let receiver_type = self.parse_expression_type(expression)?;
+
+        match self
+            .contract_definition(&receiver_type)
+            .map(|definition| &definition.ty)
+        {
+            Some(ContractTy::Contract(_))
+            | Some(ContractTy::Abstract(_))
+            | Some(ContractTy::Interface(_)) => Some(receiver_type),
+            _ => None,
+        }
+    }
+
+    /// Parses a Solang type expression to String
+    ///
+    /// `ty` the original Solang type expression
+    ///
+    /// Returns the parsed `String`, e.g. `uint256`, `IERC20` or `EnumerableSet.AddressSet`
+    fn parse_type(&self, ty: &SolangExpression) -> String {
+        match ty {
+            SolangExpression::Variable(identifier) => identifier.name.clone(),
+            SolangExpression::MemberAccess(_, left, right) => {
+                format!("{}.{}", self.parse_type(left), right.name)
+            }
+            SolangExpression::ArraySubscript(_, left, _) => format!("{}[]", self.parse_type(left)),
+            SolangExpression::Type(_, ty) => {
+                match ty {
+                    Type::Address => String::from("address"),
+                    Type::AddressPayable | Type::Payable => String::from("address payable"),
+                    Type::Bool => String::from("bool"),
+                    Type::String => String::from("string"),
+                    Type::Int(size) => format!("int{size}"),
+                    Type::Uint(size) => format!("uint{size}"),
+                    Type::Bytes(size) => format!("bytes{size}"),
+                    Type::DynamicBytes => String::from("bytes"),
+                    Type::Mapping { key, value, .. } => {
+                        format!(
+                            "mapping({} => {})",
+                            self.parse_type(key),
+                            self.parse_type(value)
+                        )
+                    }
+                    Type::Function { .. } => String::from("function"),
+                    Type::Rational => String::from("rational"),
+                }
+            }
+            _ => String::from("_"),
+        }
+    }
+
+    /// Parses multiple Solang expression enum variants to Sol2Ink expression enum variants
+    ///
+    /// `expressions` the original Solang expression enum variants
+    /// `location` the location where the expression is [being called](fn@parse_variable_access_location)
+    ///
+    /// Returns the vec of parsed `Expression` enum variant
+    fn parse_expression_vec(&mut self, expressions: &[SolangExpression]) -> Vec<Call> {
+        expressions
+            .iter()
+            .flat_map(|expression| self.parse_expression(expression))
+            .collect()
+    }
+
+    /// Parses a Solang `IdentifierPath` struct to String
+    ///
+    /// `identifier_path` the original Solang identifier
+    ///
+    /// Returns the parsed `String`
+    fn parse_identifier_path(&self, identifier_path: &IdentifierPath) -> String {
+        identifier_path
+            .identifiers
+            .iter()
+            .map(|identifier| identifier.name.clone())
+            .collect::<Vec<String>>()
             .join(".")
     }
 
+    /// Returns the call of a function accessible from the current contract
+    ///
+    /// Functions declared at the file level of another file are called like library functions
+    ///
+    /// `function_header` the header of the called function
+    /// `symbol` the called function
+    fn parse_function_access(&self, function_header: &FunctionHeader, symbol: &Symbol) -> Call {
+        if symbol.contract != self.current_contract
+            && !self
+                .contract_definitions
+                .contains_key(&(symbol.source_unit.clone(), symbol.contract.clone()))
+        {
+            return Call::Library(symbol.clone())
+        }
+
+        if function_header.view {
+            Call::Read(CallType::CallingFunction, symbol.clone())
+        } else {
+            Call::Write(CallType::CallingFunction, symbol.clone())
+        }
+    }
+
+    /// Returns the symbol of a struct used in the current contract
+    ///
+    /// Structs of the contract are preferred to the ones declared at the file level,
+    /// the ones of the current file are preferred to the ones of the other files
+    ///
+    /// `name` the name of the struct
+    fn resolve_struct(&self, name: &str) -> Symbol {
+        let symbol = self.symbol(&self.current_contract, name);
+
+        if self.structs.contains_key(&symbol) {
+            return symbol
+        }
+
+        self.structs
+            .keys()
+            .filter(|known| {
+                known.member == name
+                    && !self
+                        .contract_definitions
+                        .contains_key(&(known.source_unit.clone(), known.contract.clone()))
+            })
+            .min_by_key(|known| (known.source_unit != self.current_source_unit, *known))
+            .cloned()
+            .unwrap_or(symbol)
+    }
+
+    /// Returns the symbol of a member of a contract visible in the current file
+    ///
+    /// `contract` the name of the contract
+    /// `member` the name of the member
+    fn symbol(&self, contract: &str, member: &str) -> Symbol {
+        let (source_unit, contract) = self.qualify(&self.current_source_unit, contract);
+
+        Symbol::new(&source_unit, &contract, member)
+    }
+
+    /// Returns the source unit defining a contract visible in a file and the name of the contract
+    ///
+    /// Contracts which are not visible through the imports are looked up in all the parsed files
+    ///
+    /// `source_unit` the path of the file using the contract
+    /// `contract` the name of the contract
+    fn qualify(&self, source_unit: &str, contract: &str) -> (String, String) {
+        let defining = self
+            .source_units
+            .get(&(source_unit.to_string(), contract.to_string()))
+            .or_else(|| {
+                self.contract_definitions
+                    .keys()
+                    .filter(|(_, name)| name == contract)
+                    .map(|(defining, _)| defining)
+                    .min()
+            })
+            .cloned()
+            .unwrap_or_else(|| source_unit.to_string());
+
+        (defining, contract.to_string())
+    }
+
+    /// Returns the linearization of a contract visible in the current file, the contract alone
+    /// if it can not be linearized
+    ///
+    /// `contract` the name of the contract
+    fn linearize_visible(&self, contract: &str) -> Vec<(String, String)> {
+        let contract = self.qualify(&self.current_source_unit, contract);
+
+        self.linearize(&contract).unwrap_or_else(|_| vec![contract])
+    }
+
+    /// Returns the definition of a contract visible in the current file
+    ///
+    /// `contract` the name of the contract
+    fn contract_definition(&self, contract: &str) -> Option<&ContractDefinition> {
+        self.contract_definitions
+            .get(&self.qualify(&self.current_source_unit, contract))
+    }
+
     /// Parses a Solang `Identifier` struct to String
     ///
     /// `identifier` the original Solang identifier
@@ -1225,19 +3981,29 @@ macro_rules! initialize_parser {
         let mut fields_map = HashMap::new();
         let mut modifier_map = HashMap::new();
         let mut storage_pointers = HashMap::new();
+        let mut storage_namespaces = HashMap::new();
         let mut structs = HashMap::new();
-        let mut local_variables = HashMap::new();
-        let mut local_variables_declared = HashMap::new();
+        let mut scopes = Vec::new();
         let mut storage_access = HashMap::new();
+        let mut contract_definitions = HashMap::new();
+        let mut source_units = HashMap::new();
+        let mut free_functions = HashMap::new();
+        let mut using_directives = HashMap::new();
+        let mut errors = HashMap::new();
 
         let mut $parser = Parser::new(
             &mut fields_map,
             &mut modifier_map,
             &mut storage_pointers,
+            &mut storage_namespaces,
             &mut structs,
-            &mut local_variables,
-            &mut local_variables_declared,
+            &mut scopes,
             &mut storage_access,
+            &mut contract_definitions,
+            &mut source_units,
+            &mut free_functions,
+            &mut using_directives,
+            &mut errors,
         );
     };
 }
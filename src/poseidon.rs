@@ -1,9 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{
+    BTreeMap,
+    HashMap,
+};
 
-use crate::structures::{
-    Call,
-    Contract,
-    PoseidonOptions,
+use crate::{
+    flow::writes_after_interactions,
+    structures::{
+        Call,
+        CallType,
+        Contract,
+        Expression,
+        Function,
+        FunctionHeader,
+        Interface,
+        PoseidonOptions,
+        Recipient,
+        Symbol,
+    },
 };
 
 // Lore: Triton was the father of little mermaid.
@@ -11,7 +24,9 @@ use crate::structures::{
 
 pub fn generate_mermaid(
     vec: &Vec<Contract>,
-    slots_map: &HashMap<String, Vec<String>>,
+    interfaces: &[Interface],
+    slots_map: &HashMap<Symbol, Vec<String>>,
+    namespaces: &[String],
     options: &PoseidonOptions,
 ) -> String {
     let mut out = String::new();
@@ -19,6 +34,16 @@ pub fn generate_mermaid(
     out.push_str("graph LR\n");
 
     let mut write_access = HashMap::new();
+    // contracts and their functions which are charted, relations are only drawn between them
+    let mut charted_contracts = Vec::default();
+    let mut charted_functions = Vec::default();
+    // actors and the external state-changing functions they may call
+    let mut actor_calls: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    // ETH received by the payable entry points and sent by the functions
+    let mut value_flow = String::new();
+    let world_node = Symbol::new("", "External", "world").mermaid_id("w");
+    // functions and the contracts they deploy, the created contract and how it is created
+    let mut creations = Vec::default();
 
     for contract in vec.clone() {
         for function in contract.functions.clone() {
@@ -27,16 +52,41 @@ pub fn generate_mermaid(
                     // @todo this must be processed before
                     continue
                 }
+                if call.is_immutable_access() {
+                    // immutables are charted only in the deployment diagram
+                    continue
+                }
                 if function.header.view && options.omit_read_storage {
                     continue;
                 }
                 write_access.insert(
-                    format!("f_{}_{}", contract.name, function.header.name.clone(),),
+                    contract
+                        .symbol(&function.header.signature())
+                        .mermaid_id("f"),
                     (),
                 );
                 if options.omit_read_storage && call.is_read_storage() {
                     continue
                 }
+                if let Call::Revert(..)
+                | Call::AddressCall(..)
+                | Call::Guard(..)
+                | Call::OriginCheck
+                | Call::SendValue(..)
+                | Call::Create(..) = call
+                {
+                    // errors, low-level calls and guards are listed in the report, value transfers
+                    // and created contracts are charted after the contracts
+                    continue
+                }
+                if call.is_flow_marker() {
+                    continue
+                }
+                if let Call::Delegate = call {
+                    // the implementation is charted with the contract forwarding to it
+                    write_access.insert(contract.symbol("delegatecall").mermaid_id("d"), ());
+                    continue
+                }
                 write_access.insert(call.to_string(), ());
             }
         }
@@ -45,6 +95,11 @@ pub fn generate_mermaid(
     for contract in vec {
         let mut sub_graph = String::new();
         let mut contains_stuff = false;
+        let signatures = contract
+            .functions
+            .iter()
+            .map(|function| function.header.signature())
+            .collect::<Vec<_>>();
 
         sub_graph.push_str(format!("subgraph {}\n", contract.name.clone()).as_str());
 
@@ -54,21 +109,49 @@ pub fn generate_mermaid(
             sub_graph.push_str("subgraph Storage\n");
 
             for storage_field in contract.fields.clone() {
-                if !write_access.contains_key(
-                    format!("s_{}_{}", contract.name, storage_field.name.clone()).as_str(),
-                ) {
+                let node = contract.symbol(&storage_field.name).mermaid_id("s");
+                if !write_access.contains_key(&node) {
                     continue
                 }
                 contains_stuff = true;
+                sub_graph.push_str(
+                    format!("{node}[({})]:::storage\n", storage_field.name.clone()).as_str(),
+                )
+            }
+
+            sub_graph.push_str("end\n");
+            sub_graph.push('\n');
+        }
+
+        // events might be declared in a base contract or an interface, so we check emits as well
+        let mut events = contract
+            .events
+            .iter()
+            .map(|event| event.name.clone())
+            .collect::<Vec<_>>();
+        for function in contract.functions.iter() {
+            for call in function.calls.iter() {
+                if let Call::Emit(event) = call {
+                    if !events.contains(&event.member) {
+                        events.push(event.member.clone());
+                    }
+                }
+            }
+        }
+        events.retain(|event| write_access.contains_key(&contract.symbol(event).mermaid_id("e")));
+
+        if !events.is_empty() {
+            contains_stuff = true;
+            sub_graph.push_str("subgraph Events\n");
+
+            for event in events {
                 sub_graph.push_str(
                     format!(
-                        "s_{}_{}[({})]:::storage\n",
-                        contract.name,
-                        storage_field.name.clone(),
-                        storage_field.name.clone()
+                        "{}>{event}]:::event\n",
+                        contract.symbol(&event).mermaid_id("e")
                     )
                     .as_str(),
-                )
+                );
             }
 
             sub_graph.push_str("end\n");
@@ -76,64 +159,81 @@ pub fn generate_mermaid(
         }
 
         for function in contract.functions.clone() {
-            if (function.header.view
-                && !write_access.contains_key(
-                    format!("f_{}_{}", contract.name, function.header.name.clone()).as_str(),
-                ))
+            let node = contract
+                .symbol(&function.header.signature())
+                .mermaid_id("f");
+            if (function.header.view && !write_access.contains_key(&node))
                 || !function.header.external
             {
                 continue
             }
             contains_stuff = true;
+            charted_functions.push(node.clone());
             sub_graph.push_str(
                 format!(
-                    "f_{}_{}[{}]:::{}\n",
-                    contract.name,
-                    function.header.name.clone(),
-                    function.header.name.clone(),
-                    match (function.header.external, function.header.view) {
-                        (true, true) => "external_view",
-                        (true, false) => "external",
-                        (false, true) => "internal_view",
-                        (false, false) => "internal",
-                    }
+                    "{node}[{}]:::{}\n",
+                    function_label(&function.header.signature(), &signatures),
+                    function_class(&function.header)
                 )
                 .as_str(),
-            )
+            );
+
+            if options.value_flow && function.header.payable {
+                value_flow.push_str(format!("{world_node} -->|ETH| {node}\n").as_str());
+            }
+
+            if options.actors && !function.header.view {
+                let mut actors = contract
+                    .guards(&function)
+                    .iter()
+                    .map(|guard| actor_name(guard))
+                    .collect::<Vec<_>>();
+                if actors.is_empty() {
+                    actors.push(String::from("Anyone"));
+                }
+                actors.sort();
+                actors.dedup();
+                for actor in actors {
+                    actor_calls.entry(actor).or_default().push(node.clone());
+                }
+            }
         }
 
         for function in contract.functions.clone() {
-            if !write_access.contains_key(
-                format!("f_{}_{}", contract.name, function.header.name.clone()).as_str(),
-            ) || function.header.external
-            {
+            let node = contract
+                .symbol(&function.header.signature())
+                .mermaid_id("f");
+            if !write_access.contains_key(&node) || function.header.external {
                 continue
             }
             contains_stuff = true;
+            charted_functions.push(node.clone());
             sub_graph.push_str(
                 format!(
-                    "f_{}_{}[{}]:::{}\n",
-                    contract.name,
-                    function.header.name.clone(),
-                    function.header.name.clone(),
-                    match (function.header.external, function.header.view) {
-                        (true, true) => "external_view",
-                        (true, false) => "external",
-                        (false, true) => "internal_view",
-                        (false, false) => "internal",
-                    }
+                    "{node}[{}]:::{}\n",
+                    function_label(&function.header.signature(), &signatures),
+                    function_class(&function.header)
                 )
                 .as_str(),
             )
         }
 
+        // proxies forward the calls they do not implement to their implementation
+        let delegate_node = contract.symbol("delegatecall").mermaid_id("d");
+        if write_access.contains_key(&delegate_node) {
+            contains_stuff = true;
+            sub_graph
+                .push_str(format!("{delegate_node}{{{{implementation}}}}:::delegate\n").as_str());
+        }
+
         sub_graph.push('\n');
         sub_graph.push_str("end\n");
 
         for function in contract.functions.clone() {
-            if !write_access.contains_key(
-                format!("f_{}_{}", contract.name, function.header.name.clone()).as_str(),
-            ) {
+            let node = contract
+                .symbol(&function.header.signature())
+                .mermaid_id("f");
+            if !write_access.contains_key(&node) {
                 continue
             }
             contains_stuff = true;
@@ -143,17 +243,16 @@ pub fn generate_mermaid(
             filtered_calls.dedup();
             filtered_calls = filtered_calls
                 .iter()
+                .filter(|call| !call.is_immutable_access())
                 .filter(|call| {
-                    if let Call::ReadStorage(call_type, contract, member) = call {
-                        !filtered_calls.contains(&Call::Write(
-                            call_type.clone(),
-                            contract.clone(),
-                            member.clone(),
-                        )) && !filtered_calls.contains(&Call::WriteStorage(
-                            call_type.clone(),
-                            contract.clone(),
-                            member.clone(),
-                        ))
+                    if let Call::ReadStorage(call_type, symbol) = call {
+                        !filtered_calls.contains(&Call::Write(call_type.clone(), symbol.clone()))
+                            && !filtered_calls
+                                .contains(&Call::WriteStorage(call_type.clone(), symbol.clone()))
+                            && !filtered_calls.iter().any(|other| {
+                                matches!(other, Call::WriteStorageField(other_type, other_symbol, _)
+                                    if other_type == call_type && other_symbol == symbol)
+                            })
                     } else {
                         true
                     }
@@ -162,32 +261,81 @@ pub fn generate_mermaid(
                 .collect();
             // one function may also read and write to storage, we will favor write
 
+            // writes to fields of the same storage are charted as a single edge
+            let mut field_writes: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+            for call in filtered_calls.iter() {
+                if let Call::WriteStorageField(call_type, symbol, field) = call {
+                    if filtered_calls
+                        .contains(&Call::WriteStorage(call_type.clone(), symbol.clone()))
+                    {
+                        continue
+                    }
+                    field_writes
+                        .entry(call.to_string())
+                        .or_default()
+                        .push(field.clone());
+                }
+            }
+
+            for (storage, fields) in field_writes {
+                sub_graph
+                    .push_str(format!("{node} -->|{}| {storage}\n", fields.join(", ")).as_str());
+            }
+
+            // writes after interactions may be reentered, so we highlight them
+            let mut late_writes = writes_after_interactions(contract, &function)
+                .iter()
+                .map(|finding| finding.write.to_string())
+                .collect::<Vec<_>>();
+            late_writes.sort();
+            late_writes.dedup();
+            for storage in late_writes {
+                sub_graph.push_str(format!("{node} ==>|after interaction| {storage}\n").as_str());
+            }
+
             for call in filtered_calls {
                 match call {
-                    Call::Read(..) | Call::Write(..) | Call::WriteStorage(..) => {
+                    // transient storage is cleared after the transaction, so we label it
+                    Call::WriteStorage(CallType::CallingTransientStorage, _) => {
+                        sub_graph.push_str(
+                            format!("{node} -->|transient| {}\n", call.to_string()).as_str(),
+                        );
+                    }
+                    Call::ReadStorage(CallType::CallingTransientStorage, _)
+                        if !options.omit_read_storage =>
+                    {
                         sub_graph.push_str(
+                            format!("{node} -.->|transient| {}\n", call.to_string()).as_str(),
+                        );
+                    }
+                    Call::Read(..)
+                    | Call::Write(..)
+                    | Call::WriteStorage(..)
+                    | Call::External(..)
+                    | Call::Emit(..) => {
+                        sub_graph.push_str(format!("{node} --> {}\n", call.to_string()).as_str());
+                    }
+                    Call::ReadStorage(..) if !options.omit_read_storage => {
+                        sub_graph.push_str(format!("{node} -.-> {}\n", call.to_string()).as_str());
+                    }
+                    Call::Delegate => {
+                        sub_graph.push_str(
+                            format!("{node} -->|delegatecall| {delegate_node}\n").as_str(),
+                        );
+                    }
+                    Call::Create(contract, method) => {
+                        creations.push((node.clone(), contract, method));
+                    }
+                    Call::SendValue(method, recipient) if options.value_flow => {
+                        value_flow.push_str(
                             format!(
-                                "f_{}_{} --> {}\n",
-                                contract.name,
-                                function.header.name.clone(),
-                                call.to_string()
+                                "{node} -->|\"{}\"| {world_node}\n",
+                                value_label(&method, &recipient)
                             )
                             .as_str(),
                         );
                     }
-                    Call::ReadStorage(..) => {
-                        if !options.omit_read_storage {
-                            sub_graph.push_str(
-                                format!(
-                                    "f_{}_{} -.-> {}\n",
-                                    contract.name,
-                                    function.header.name.clone(),
-                                    call.to_string()
-                                )
-                                .as_str(),
-                            );
-                        }
-                    }
                     _ => (),
                 }
             }
@@ -197,24 +345,137 @@ pub fn generate_mermaid(
 
         if contains_stuff {
             out.push_str(&sub_graph);
+            charted_contracts.push(contract.name.clone());
+        }
+    }
+
+    // interfaces are boundaries of the modules, so all of their functions are charted
+    for interface in interfaces {
+        out.push_str(format!("subgraph {}\n", interface.name).as_str());
+        let signatures = interface
+            .function_headers
+            .iter()
+            .map(|function_header| function_header.signature())
+            .collect::<Vec<_>>();
+        for function_header in interface.function_headers.iter() {
+            out.push_str(
+                format!(
+                    "{}[{}]:::{}\n",
+                    interface
+                        .symbol(&function_header.signature())
+                        .mermaid_id("f"),
+                    function_label(&function_header.signature(), &signatures),
+                    function_class(function_header)
+                )
+                .as_str(),
+            );
+        }
+        out.push_str("end\n");
+
+        for contract in vec.iter() {
+            if charted_contracts.contains(&contract.name) && contract.base.contains(&interface.name)
+            {
+                out.push_str(
+                    format!("{} -.->|implements| {}\n", contract.name, interface.name).as_str(),
+                );
+            }
+
+            if !options.link_implementations || !contract.linearization.contains(&interface.name) {
+                continue
+            }
+            for function_header in interface.function_headers.iter() {
+                let implementation = contract
+                    .symbol(&function_header.signature())
+                    .mermaid_id("f");
+                if charted_functions.contains(&implementation) {
+                    out.push_str(
+                        format!(
+                            "{} -. implementation .-> {implementation}\n",
+                            interface
+                                .symbol(&function_header.signature())
+                                .mermaid_id("f"),
+                        )
+                        .as_str(),
+                    );
+                }
+            }
         }
     }
 
-    for slot in slots_map {
+    // functions of contracts and interfaces which are called but not charted
+    let mut external_targets: BTreeMap<String, Vec<Symbol>> = BTreeMap::new();
+
+    for contract in vec {
+        for function in contract.functions.iter() {
+            for call in function.calls.iter() {
+                if let Call::External(symbol, _) = call {
+                    if vec.iter().any(|contract| contract.name == symbol.contract)
+                        || interfaces
+                            .iter()
+                            .any(|interface| interface.name == symbol.contract)
+                    {
+                        continue
+                    }
+                    let functions = external_targets.entry(symbol.contract.clone()).or_default();
+                    if !functions.contains(symbol) {
+                        functions.push(symbol.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    for (receiver, functions) in external_targets {
+        out.push_str(format!("subgraph {receiver}\n").as_str());
+        let signatures = functions
+            .iter()
+            .map(|function| function.member.clone())
+            .collect::<Vec<_>>();
+        for function in functions {
+            out.push_str(
+                format!(
+                    "{}[{}]:::external\n",
+                    function.mermaid_id("f"),
+                    function_label(&function.member, &signatures)
+                )
+                .as_str(),
+            );
+        }
+        out.push_str("end\n");
+    }
+
+    // storage namespaces are shared by the contracts using them, so they are always grouped
+    let mut namespaced_storage: BTreeMap<String, String> = BTreeMap::new();
+
+    for (slot, fields) in slots_map {
         let mut slot_out = String::new();
         let mut has_stuff = false;
 
-        for field in slot.1 {
-            if !write_access.contains_key(format!("s_{}_{}", slot.0, field).as_str()) {
+        for field in fields {
+            let node = slot.field(field).mermaid_id("s");
+            if !write_access.contains_key(&node) {
                 continue
             }
             has_stuff = true;
-            slot_out.push_str(format!("s_{}_{field}[({field})]:::storage\n", slot.0).as_str());
+            slot_out.push_str(format!("{node}[({field})]:::storage\n").as_str());
         }
 
-        if has_stuff {
+        if has_stuff && namespaces.contains(&slot.contract) {
+            namespaced_storage
+                .entry(slot.contract.clone())
+                .or_default()
+                .push_str(&slot_out);
+        } else if has_stuff {
             if options.group_floating_storage {
-                out.push_str(format!("subgraph {}Storage\n", slot.0).as_str());
+                out.push_str(
+                    format!(
+                        "subgraph {}[\"{}.{} Storage\"]\n",
+                        slot.mermaid_id("g"),
+                        slot.contract,
+                        slot.member
+                    )
+                    .as_str(),
+                );
             }
             out.push_str(&slot_out);
             if options.group_floating_storage {
@@ -223,10 +484,84 @@ pub fn generate_mermaid(
         }
     }
 
+    for (namespace, namespace_out) in namespaced_storage {
+        out.push_str(
+            format!(
+                "subgraph {}[{namespace}]\n",
+                Symbol::new("", &namespace, "").mermaid_id("g")
+            )
+            .as_str(),
+        );
+        out.push_str(&namespace_out);
+        out.push_str("end\n");
+    }
+
+    // factories point at the subgraphs of the contracts they deploy
+    let mut created_nodes = Vec::default();
+    for (function, contract, method) in creations {
+        let label = if method == "create" {
+            String::from("deploys")
+        } else {
+            format!("deploys with {method}")
+        };
+        let created = match contract {
+            Some(contract) if charted_contracts.contains(&contract) => contract,
+            // the created contract is not charted or not known, e.g. a clone
+            contract => {
+                let name = contract.unwrap_or(method);
+                let node = Symbol::new("", "Created", &name).mermaid_id("c");
+                if !created_nodes.contains(&node) {
+                    out.push_str(format!("{node}[[{name}]]:::created\n").as_str());
+                    created_nodes.push(node.clone());
+                }
+                node
+            }
+        };
+        out.push_str(format!("{function} -->|{label}| {created}\n").as_str());
+    }
+
+    if !value_flow.is_empty() {
+        out.push_str(format!("{world_node}((External world)):::world\n").as_str());
+        out.push_str(&value_flow);
+    }
+
+    // actors answer who can change what, so they point at the entry points they may call
+    if !actor_calls.is_empty() {
+        out.push_str("subgraph Actors\n");
+        for actor in actor_calls.keys() {
+            out.push_str(
+                format!(
+                    "{}[\"{}\"]:::actor\n",
+                    Symbol::new("", "Actors", actor).mermaid_id("a"),
+                    actor.replace('"', "#quot;")
+                )
+                .as_str(),
+            );
+        }
+        out.push_str("end\n");
+
+        for (actor, functions) in actor_calls {
+            for function in functions {
+                out.push_str(
+                    format!(
+                        "{} --> {function}\n",
+                        Symbol::new("", "Actors", &actor).mermaid_id("a")
+                    )
+                    .as_str(),
+                );
+            }
+        }
+    }
+
     out.push_str("classDef storage fill:#ff00ff,stroke:#333,stroke-width:2px;\n");
     out.push_str("classDef external fill:#ff0000,stroke:#333,stroke-width:2px;\n");
     out.push_str("classDef external_view fill:#ffff00,stroke:#333,stroke-width:2px;\n");
+    out.push_str("classDef external_payable fill:#ff8800,stroke:#333,stroke-width:2px;\n");
+    out.push_str("classDef delegate fill:#8888ff,stroke:#333,stroke-width:2px;\n");
+    out.push_str("classDef event fill:#00ffff,stroke:#333,stroke-width:2px;\n");
     out.push_str("classDef actor fill:#00ff00,stroke:#333,stroke-width:2px;\n");
+    out.push_str("classDef world fill:#88ff88,stroke:#333,stroke-width:2px;\n");
+    out.push_str("classDef created fill:#ffffff,stroke:#333,stroke-width:2px;\n");
     out.push_str(
         "classDef internal fill:#ff0000,stroke:#333,stroke-width:2px,stroke-dasharray: 5 5;\n",
     );
@@ -236,3 +571,283 @@ pub fn generate_mermaid(
 
     out
 }
+
+/// Generates the diagram of storage and immutables set while deploying the contracts
+///
+/// Constructors are chained in the order in which they are executed on deployment,
+/// functions guarded by `initializer` or `reinitializer` are charted next to them
+pub fn generate_deployment_mermaid(vec: &Vec<Contract>) -> String {
+    let mut out = String::new();
+
+    out.push_str("graph LR\n");
+
+    // storage accessed through storage pointers is not part of any contract
+    let mut floating_storage: BTreeMap<String, String> = BTreeMap::new();
+
+    for contract in vec {
+        let mut steps = Vec::default();
+
+        for constructor in contract.constructors.iter() {
+            steps.push((
+                contract.symbol(&constructor.header.name).mermaid_id("c"),
+                format!("{}.constructor", constructor.header.name),
+                "constructor",
+                collect_writes(contract, &constructor.calls, &mut Vec::default()),
+            ));
+        }
+
+        for function in contract.functions.iter() {
+            if !is_initializer(function) {
+                continue
+            }
+            steps.push((
+                contract
+                    .symbol(&function.header.signature())
+                    .mermaid_id("f"),
+                function.header.name.clone(),
+                "initializer",
+                collect_writes(contract, &function.calls, &mut Vec::default()),
+            ));
+        }
+
+        if steps.iter().all(|(_, _, _, writes)| writes.is_empty()) {
+            continue
+        }
+
+        let written = steps
+            .iter()
+            .flat_map(|(_, _, _, writes)| writes.iter().map(|call| call.to_string()))
+            .collect::<Vec<_>>();
+
+        out.push_str(format!("subgraph {}\n", contract.name).as_str());
+        out.push('\n');
+
+        let fields = contract
+            .fields
+            .iter()
+            .filter(|field| written.contains(&contract.symbol(&field.name).mermaid_id("s")))
+            .collect::<Vec<_>>();
+
+        if !fields.is_empty() {
+            out.push_str("subgraph Storage\n");
+            for field in fields {
+                out.push_str(
+                    format!(
+                        "{}[({})]:::storage\n",
+                        contract.symbol(&field.name).mermaid_id("s"),
+                        field.name
+                    )
+                    .as_str(),
+                );
+            }
+            out.push_str("end\n");
+            out.push('\n');
+        }
+
+        let immutables = contract
+            .immutables
+            .iter()
+            .filter(|field| written.contains(&contract.symbol(&field.name).mermaid_id("i")))
+            .collect::<Vec<_>>();
+
+        if !immutables.is_empty() {
+            out.push_str("subgraph Immutables\n");
+            for immutable in immutables {
+                out.push_str(
+                    format!(
+                        "{}[/{}/]:::immutable\n",
+                        contract.symbol(&immutable.name).mermaid_id("i"),
+                        immutable.name
+                    )
+                    .as_str(),
+                );
+            }
+            out.push_str("end\n");
+            out.push('\n');
+        }
+
+        for (node, label, class, _) in steps.iter() {
+            out.push_str(format!("{node}[{label}]:::{class}\n").as_str());
+        }
+
+        out.push('\n');
+        out.push_str("end\n");
+
+        // constructors are executed one after another
+        let chain = steps
+            .iter()
+            .filter(|(_, _, class, _)| *class == "constructor")
+            .map(|(node, _, _, _)| node.clone())
+            .collect::<Vec<_>>();
+        if chain.len() > 1 {
+            out.push_str(format!("{}\n", chain.join(" ==> ")).as_str());
+        }
+
+        for (node, _, _, writes) in steps.iter() {
+            for call in writes {
+                if let Call::WriteStorage(CallType::CallingStoragePointer, symbol)
+                | Call::WriteStorageField(CallType::CallingStoragePointer, symbol, _) = call
+                {
+                    // the member of a storage pointer is `Struct.field`
+                    let field = symbol.member.rsplit('.').next().unwrap_or_default();
+                    floating_storage.insert(call.to_string(), field.to_string());
+                }
+                out.push_str(format!("{node} --> {}\n", call.to_string()).as_str());
+            }
+        }
+
+        out.push('\n');
+    }
+
+    for (node, field) in floating_storage {
+        out.push_str(format!("{node}[({field})]:::storage\n").as_str());
+    }
+
+    out.push_str("classDef storage fill:#ff00ff,stroke:#333,stroke-width:2px;\n");
+    out.push_str("classDef immutable fill:#ff88ff,stroke:#333,stroke-width:2px;\n");
+    out.push_str("classDef constructor fill:#ff8800,stroke:#333,stroke-width:2px;\n");
+    out.push_str(
+        "classDef initializer fill:#ff8800,stroke:#333,stroke-width:2px,stroke-dasharray: 5 5;\n",
+    );
+
+    out
+}
+
+/// Returns true if the function is guarded by `initializer` or `reinitializer` modifier
+fn is_initializer(function: &Function) -> bool {
+    function.header.modifiers.iter().any(|modifier| {
+        let Expression::Modifier(name, ..) = modifier;
+        name == "initializer" || name == "reinitializer"
+    })
+}
+
+/// Returns the name of the actor required by a guard, e.g. `Owner` of `_owner` or `owner()`
+///
+/// Roles are named by their constants, e.g. `MINTER_ROLE`, and predicates by what they check,
+/// e.g. `Operator` of `isOperator`
+///
+/// `guard` the role or account required by the guard
+fn actor_name(guard: &str) -> String {
+    // `s.owner` and `owner()` name the same account as `owner`
+    let path = guard.trim_end_matches("()");
+    if path.contains(['(', '[', ' ', '"']) {
+        return guard.to_string()
+    }
+    let name = path
+        .rsplit('.')
+        .next()
+        .unwrap_or(path)
+        .trim_start_matches('_');
+    let name = name
+        .strip_prefix("is")
+        .filter(|checked| checked.starts_with(|first: char| first.is_ascii_uppercase()))
+        .unwrap_or(name);
+
+    if name.is_empty() {
+        return guard.to_string()
+    }
+    if name
+        .chars()
+        .all(|character| !character.is_ascii_lowercase())
+    {
+        return name.to_string()
+    }
+
+    let mut characters = name.chars();
+    characters
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + characters.as_str())
+        .unwrap_or_default()
+}
+
+/// Returns the label of an edge sending ETH, e.g. `transfer to parameter to`
+///
+/// `method` how the value is sent, e.g. `transfer` or `selfdestruct`
+/// `recipient` the recipient of the value
+fn value_label(method: &str, recipient: &Recipient) -> String {
+    let recipient = match recipient {
+        Recipient::Sender => String::from("msg.sender"),
+        Recipient::Storage(name) => format!("stored {name}"),
+        Recipient::Parameter(name) => format!("parameter {name}"),
+        Recipient::Other(expression) => expression.clone(),
+    };
+
+    // quotes end the label in mermaid
+    format!("{method} to {}", recipient.replace('"', "#quot;"))
+}
+
+/// Returns the label of a function node, overloaded functions are labeled by their signatures
+///
+/// `signature` the signature of the function, e.g. `transfer(address,uint256)`
+/// `signatures` the signatures of the functions charted together with the function
+fn function_label(signature: &str, signatures: &[String]) -> String {
+    let name = |signature: &str| signature.split('(').next().unwrap_or_default().to_string();
+    let overloads = signatures
+        .iter()
+        .filter(|known| name(known) == name(signature))
+        .count();
+
+    if overloads > 1 {
+        // parentheses have a meaning in mermaid, so the label is quoted
+        format!("\"{signature}\"")
+    } else {
+        name(signature)
+    }
+}
+
+/// Returns the class of the node of a function, payable functions are the ones receiving value
+///
+/// `function_header` the header of the function
+fn function_class(function_header: &FunctionHeader) -> &'static str {
+    match (
+        function_header.external,
+        function_header.view,
+        function_header.payable,
+    ) {
+        (true, _, true) => "external_payable",
+        (true, true, false) => "external_view",
+        (true, false, false) => "external",
+        (false, true, _) => "internal_view",
+        (false, false, _) => "internal",
+    }
+}
+
+/// Collects the writes to storage and immutables made by the calls, including the writes made by
+/// the functions of the contract which are called
+///
+/// `contract` the contract the calls are made in
+/// `calls` the calls to collect the writes from
+/// `visited` the functions which were already visited
+fn collect_writes(contract: &Contract, calls: &[Call], visited: &mut Vec<String>) -> Vec<Call> {
+    let mut writes = Vec::default();
+
+    for call in calls {
+        match call {
+            Call::WriteStorage(..) | Call::WriteStorageField(..) if !writes.contains(call) => {
+                writes.push(call.clone())
+            }
+            // functions of the bases were already merged into the contract
+            Call::Read(CallType::CallingFunction, symbol)
+            | Call::Write(CallType::CallingFunction, symbol)
+                if !visited.contains(&symbol.member) =>
+            {
+                visited.push(symbol.member.clone());
+
+                if let Some(function) = contract
+                    .functions
+                    .iter()
+                    .find(|function| function.header.signature() == symbol.member)
+                {
+                    for write in collect_writes(contract, &function.calls, visited) {
+                        if !writes.contains(&write) {
+                            writes.push(write)
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    writes
+}
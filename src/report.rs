@@ -0,0 +1,221 @@
+use crate::{
+    flow::writes_after_interactions,
+    structures::{
+        Call,
+        Contract,
+    },
+};
+
+/// Generates the text report of the charted contracts
+///
+/// Each contract lists the custom errors its functions may revert with and the storage its
+/// functions may write after interacting with other contracts or accounts
+pub fn generate_report(vec: &[Contract]) -> String {
+    let mut out = String::new();
+
+    for contract in vec {
+        let errors = contract
+            .functions
+            .iter()
+            .map(|function| (function.header.signature(), contract.errors(function)))
+            .filter(|(_, errors)| !errors.is_empty())
+            .collect::<Vec<_>>();
+        let late_writes = contract
+            .functions
+            .iter()
+            .map(|function| {
+                let findings = writes_after_interactions(contract, function)
+                    .iter()
+                    .map(|finding| {
+                        format!(
+                            "{} after {}",
+                            describe(&finding.write),
+                            describe(&finding.interaction)
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                (function.header.signature(), findings)
+            })
+            .filter(|(_, findings)| !findings.is_empty())
+            .collect::<Vec<_>>();
+
+        if errors.is_empty() && late_writes.is_empty() {
+            continue
+        }
+
+        out.push_str(format!("# {}\n\n", contract.name).as_str());
+
+        if !errors.is_empty() {
+            out.push_str("## Errors\n\n");
+            push_items(&mut out, errors);
+        }
+
+        if !late_writes.is_empty() {
+            out.push_str("## Writes after interactions\n\n");
+            push_items(&mut out, late_writes);
+        }
+    }
+
+    out
+}
+
+/// Generates the access-control matrix of the charted contracts in Markdown
+///
+/// Each contract has a table of its external state-changing functions and the roles or accounts
+/// they require, functions writing the storage without any check are flagged
+pub fn generate_access_table(vec: &[Contract]) -> String {
+    let mut out = String::new();
+
+    for contract in vec {
+        let (guards, rows) = access_matrix(contract);
+        if rows.is_empty() {
+            continue
+        }
+
+        let mut header = vec![String::from("function")];
+        header.extend(guards.iter().map(|guard| format!("`{guard}`")));
+        header.push(String::from("unguarded writer"));
+        header.push(String::from("tx.origin check"));
+
+        out.push_str(format!("# {}\n\n", contract.name).as_str());
+        out.push_str(format!("| {} |\n", header.join(" | ")).as_str());
+        out.push_str(format!("|{}\n", " --- |".repeat(header.len())).as_str());
+        for row in rows {
+            let mut cells = vec![format!("`{}`", row.signature)];
+            cells.extend(guards.iter().map(|guard| mark(row.guards.contains(guard))));
+            cells.push(mark(row.unguarded_writer));
+            cells.push(mark(row.origin_check));
+            out.push_str(format!("| {} |\n", cells.join(" | ")).as_str());
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Generates the access-control matrix of the charted contracts in CSV
+///
+/// Each row is an external state-changing function, the columns are all the roles or accounts
+/// required by the functions of the charted contracts
+pub fn generate_access_csv(vec: &[Contract]) -> String {
+    let matrices = vec.iter().map(access_matrix).collect::<Vec<_>>();
+    let mut all_guards = matrices
+        .iter()
+        .flat_map(|(guards, _)| guards.clone())
+        .collect::<Vec<_>>();
+    all_guards.sort();
+    all_guards.dedup();
+
+    let mut header = vec![String::from("contract"), String::from("function")];
+    header.extend(all_guards.clone());
+    header.push(String::from("unguarded writer"));
+    header.push(String::from("tx.origin check"));
+
+    let mut out = format!("{}\n", csv_line(&header));
+    for (contract, (_, rows)) in vec.iter().zip(matrices) {
+        for row in rows {
+            let mut cells = vec![contract.name.clone(), row.signature];
+            cells.extend(
+                all_guards
+                    .iter()
+                    .map(|guard| row.guards.contains(guard).to_string()),
+            );
+            cells.push(row.unguarded_writer.to_string());
+            cells.push(row.origin_check.to_string());
+            out.push_str(format!("{}\n", csv_line(&cells)).as_str());
+        }
+    }
+
+    out
+}
+
+/// Row of the access-control matrix
+struct AccessRow {
+    signature: String,
+    guards: Vec<String>,    // roles or accounts required by the function
+    unguarded_writer: bool, // the function writes the storage without any check
+    origin_check: bool,     // the function checks `tx.origin`, which may be phished
+}
+
+/// Returns the roles or accounts required by the functions of a contract and the rows of its
+/// external state-changing functions
+///
+/// `contract` the contract of the matrix
+fn access_matrix(contract: &Contract) -> (Vec<String>, Vec<AccessRow>) {
+    let rows = contract
+        .functions
+        .iter()
+        .filter(|function| function.header.external && !function.header.view)
+        .map(|function| {
+            let guards = contract.guards(function);
+            AccessRow {
+                signature: function.header.signature(),
+                unguarded_writer: guards.is_empty() && contract.writes_storage(function),
+                origin_check: contract.checks_origin(function),
+                guards,
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut guards = rows
+        .iter()
+        .flat_map(|row| row.guards.clone())
+        .collect::<Vec<_>>();
+    guards.sort();
+    guards.dedup();
+
+    (guards, rows)
+}
+
+/// Returns the cell of a Markdown table marking whether the row has the property
+fn mark(value: bool) -> String {
+    String::from(if value { "x" } else { "" })
+}
+
+/// Returns the line of a CSV file, quoting the cells containing a separator or a quote
+fn csv_line(cells: &[String]) -> String {
+    cells
+        .iter()
+        .map(|cell| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Lists the items of each function
+///
+/// `out` the report
+/// `items` the signatures of the functions with their items
+fn push_items(out: &mut String, items: Vec<(String, Vec<String>)>) {
+    for (signature, items) in items {
+        out.push_str(format!("{signature}\n").as_str());
+        for item in items {
+            out.push_str(format!("  - {item}\n").as_str());
+        }
+    }
+    out.push('\n');
+}
+
+/// Returns the readable description of a storage access or an interaction,
+/// e.g. `Vault.balance` or `IERC20.transfer(address,uint256)`
+///
+/// `call` the described call
+fn describe(call: &Call) -> String {
+    match call {
+        Call::WriteStorageField(_, symbol, field) => {
+            format!("{}.{}.{field}", symbol.contract, symbol.member)
+        }
+        Call::Read(_, symbol)
+        | Call::ReadStorage(_, symbol)
+        | Call::Write(_, symbol)
+        | Call::WriteStorage(_, symbol)
+        | Call::External(symbol, _) => format!("{}.{}", symbol.contract, symbol.member),
+        Call::Delegate => String::from("delegatecall"),
+        Call::AddressCall(member) => format!("address.{member}"),
+        _ => String::default(),
+    }
+}
@@ -1,30 +1,251 @@
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum MemberType {
-    StorageField(String),
-    Function(FunctionHeader, String),
+    StorageField(Symbol, String), // storage field, type
+    Immutable(Symbol),
+    Function(Vec<(FunctionHeader, Symbol)>), // overloads of the function
+}
+
+/// Variable declared in a function, e.g. a parameter, a local or a Yul `let` binding
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LocalVariable {
+    pub ty: Option<String>, // type of the variable, `None` in assembly
+    pub storage_pointer: Option<Symbol>, // struct the variable points to if it is a storage pointer
+    pub storage: Option<Symbol>, // storage the pointer points into if known, e.g. a namespace
+    pub slot: Option<Symbol>, // storage field whose slot the variable holds, e.g. `x.slot`
+    pub alias: Option<StorageAlias>, // storage the pointer is an element or a field of if known
+    pub parameter: bool,    // the variable is a parameter of the function
+}
+
+/// Storage a local storage pointer was initialized from, e.g. `users` of `User storage u = users[id]`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageAlias {
+    pub call_type: CallType, // how the storage is accessed
+    pub storage: Symbol,     // the aliased storage variable
+    pub path: Vec<String>,   // fields of the storage variable the pointer points to
+}
+
+/// Recipient of the ETH sent by a function, told apart by the kind of the destination expression
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Recipient {
+    Sender,            // `msg.sender`, `_msgSender()` or `tx.origin`
+    Storage(String),   // address kept in a storage field or an immutable
+    Parameter(String), // address passed as a parameter of the function
+    Other(String),     // any other expression as it is written
+}
+
+/// `using ... for ...` directive attaching library functions to a type
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UsingDirective {
+    pub library: Option<String>, // `using Library for ...`
+    pub functions: Vec<String>,  // `using {function, Library.function} for ...`
+    pub ty: Option<String>,      // attached type, `None` for `*`
+    pub global: bool,
+}
+
+/// Qualified identifier of a member of a contract, e.g. a function, a storage field or a struct
+///
+/// Members of structs are accessed by their path, e.g. `Struct.field`
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Symbol {
+    pub source_unit: String, // path of the file defining the contract
+    pub contract: String,
+    pub member: String,
+}
+
+impl Symbol {
+    pub fn new(source_unit: &str, contract: &str, member: &str) -> Self {
+        Self {
+            source_unit: source_unit.to_string(),
+            contract: contract.to_string(),
+            member: member.to_string(),
+        }
+    }
+
+    /// Returns the symbol of a field of this struct
+    pub fn field(&self, field: &str) -> Self {
+        Self {
+            member: format!("{}.{field}", self.member),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the ID of the symbol in the mermaid diagram, e.g. `f_Contract_function_3_5`
+    ///
+    /// Underscores and the punctuation of signatures are escaped, so different members never
+    /// share an ID, the source unit is left out to keep the IDs readable, contracts with the same
+    /// name are told apart by the analysis only
+    pub fn mermaid_id(&self, kind: &str) -> String {
+        let escape = |name: &str| {
+            // identifiers never start with a digit, so an escaped character is never a separator
+            name.chars()
+                .map(|character| {
+                    match character {
+                        '_' => String::from("_0"),
+                        '.' => String::from("_1"),
+                        '$' => String::from("_2"),
+                        '(' => String::from("_3"),
+                        ',' => String::from("_4"),
+                        ')' => String::from("_5"),
+                        character if character.is_ascii_alphanumeric() => character.to_string(),
+                        character => format!("_9{:x}_", character as u32),
+                    }
+                })
+                .collect::<String>()
+        };
+
+        format!("{kind}_{}_{}", escape(&self.contract), escape(&self.member))
+    }
 }
 
 #[derive(Clone, Default, Debug)]
 pub struct Contract {
     pub name: String,
+    pub source_unit: String,
     pub fields: Vec<ContractField>,
+    pub immutables: Vec<ContractField>,
     pub slots: Vec<StorageSlot>,
+    pub events: Vec<Event>,
     pub constructor: Function,
+    // constructors executed on deployment, the most base one first
+    pub constructors: Vec<Function>,
     pub functions: Vec<Function>,
     pub modifiers: Vec<Function>,
     pub base: Vec<String>,
+    // the contract and all of its bases, the most derived first
+    pub linearization: Vec<String>,
     pub is_abstract: bool,
 }
 
+impl Contract {
+    /// Returns the symbol of a member of the contract
+    pub fn symbol(&self, member: &str) -> Symbol {
+        Symbol::new(&self.source_unit, &self.name, member)
+    }
+
+    /// Returns the signatures of the custom errors a function may revert with, including the
+    /// errors of the functions of the contract it calls
+    ///
+    /// `function` the function of the contract
+    pub fn errors(&self, function: &Function) -> Vec<String> {
+        let mut errors = self
+            .reachable_calls(function)
+            .into_iter()
+            .filter_map(|call| {
+                match call {
+                    Call::Revert(Some(error)) => Some(error.member),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        errors.sort();
+        errors.dedup();
+        errors
+    }
+
+    /// Returns the roles or accounts a function requires from its caller, including the checks
+    /// of its modifiers and of the functions of the contract it calls
+    ///
+    /// `function` the function of the contract
+    pub fn guards(&self, function: &Function) -> Vec<String> {
+        let mut guards = self
+            .reachable_calls(function)
+            .into_iter()
+            .filter_map(|call| {
+                match call {
+                    Call::Guard(guard) => Some(guard),
+                    _ => None,
+                }
+            })
+            .collect::<Vec<_>>();
+        guards.sort();
+        guards.dedup();
+        guards
+    }
+
+    /// Returns `true` if a function or the functions of the contract it calls check `tx.origin`
+    ///
+    /// `function` the function of the contract
+    pub fn checks_origin(&self, function: &Function) -> bool {
+        self.reachable_calls(function)
+            .iter()
+            .any(|call| matches!(call, Call::OriginCheck))
+    }
+
+    /// Returns `true` if a function may write the storage of the contract
+    ///
+    /// `function` the function of the contract
+    pub fn writes_storage(&self, function: &Function) -> bool {
+        self.reachable_calls(function).iter().any(|call| {
+            matches!(call, Call::WriteStorage(..) | Call::WriteStorageField(..))
+                && !call.is_immutable_access()
+        })
+    }
+
+    /// Returns the calls of a function and of the functions of the contract it calls
+    ///
+    /// `function` the function of the contract
+    fn reachable_calls(&self, function: &Function) -> Vec<Call> {
+        let mut calls = Vec::default();
+        self.collect_calls(
+            &function.calls,
+            &mut calls,
+            &mut vec![function.header.signature()],
+        );
+        calls
+    }
+
+    /// Collects the calls and the calls of the called functions
+    ///
+    /// `calls` the calls to collect
+    /// `collected` the collected calls
+    /// `visited` the functions which were already visited
+    fn collect_calls(&self, calls: &[Call], collected: &mut Vec<Call>, visited: &mut Vec<String>) {
+        for call in calls {
+            collected.push(call.clone());
+
+            // functions of the bases were already merged into the contract
+            if let Call::Read(CallType::CallingFunction, symbol)
+            | Call::Write(CallType::CallingFunction, symbol) = call
+            {
+                if visited.contains(&symbol.member) {
+                    continue
+                }
+                visited.push(symbol.member.clone());
+
+                if let Some(function) = self
+                    .functions
+                    .iter()
+                    .find(|function| function.header.signature() == symbol.member)
+                {
+                    let mut called = Vec::default();
+                    self.collect_calls(&function.calls, &mut called, visited);
+                    // checks of the parameters are reported by the caller with the arguments
+                    called.retain(|call| {
+                        !matches!(call, Call::Guard(guard) if function.header.param_names.contains(guard))
+                    });
+                    collected.extend(called);
+                }
+            }
+        }
+    }
+}
+
+/// Storage write which may happen after an interaction with another contract or account
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WriteAfterInteraction {
+    pub write: Call,       // the write of the storage
+    pub interaction: Call, // the first interaction made before the write
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub struct StorageSlot {
-    pub name: String,
+    pub symbol: Symbol,
     pub fields: Vec<String>,
 }
 
 impl StorageSlot {
-    pub fn new(name: String, fields: Vec<String>) -> Self {
-        Self { name, fields }
+    pub fn new(symbol: Symbol, fields: Vec<String>) -> Self {
+        Self { symbol, fields }
     }
 }
 
@@ -37,14 +258,28 @@ pub struct Library {
 #[derive(Clone, Default, Debug)]
 pub struct Interface {
     pub name: String,
+    pub source_unit: String, // path of the file defining the interface
+    pub base: Vec<String>,
     pub function_headers: Vec<FunctionHeader>,
 }
 
+impl Interface {
+    /// Returns the symbol of a function of the interface
+    pub fn symbol(&self, member: &str) -> Symbol {
+        Symbol::new(&self.source_unit, &self.name, member)
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ContractField {
     pub name: String,
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Event {
+    pub name: String,
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct Function {
     pub header: FunctionHeader,
@@ -54,19 +289,51 @@ pub struct Function {
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
 pub struct FunctionHeader {
     pub name: String,
+    pub params: Vec<String>,      // types of the parameters
+    pub param_names: Vec<String>, // names of the parameters, empty if not named
     pub external: bool,
     pub view: bool,
     pub payable: bool,
     pub modifiers: Vec<Expression>,
 }
 
+impl FunctionHeader {
+    /// Returns the signature of the function, e.g. `transfer(address,uint256)`
+    ///
+    /// Overloads of a function are told apart by their signatures
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.params.join(","))
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Call {
-    Read(CallType, String, String), // call type, contract name, call name
-    ReadStorage(CallType, String, String), // call type, contract name, call name
-    Write(CallType, String, String), // call type, contract name, call name
-    WriteStorage(CallType, String, String), // call type, contract name, call name
-    Library(String, String),        // Library, function
+    Read(CallType, Symbol),                      // call type, called member
+    ReadStorage(CallType, Symbol),               // call type, called member
+    Write(CallType, Symbol),                     // call type, called member
+    WriteStorage(CallType, Symbol),              // call type, called member
+    WriteStorageField(CallType, Symbol, String), // call type, called member, accessed field
+    Library(Symbol),                             // library function
+    External(Symbol, bool),                      // function of the receiver type, `true` if view
+    Emit(Symbol),                                // event
+    Super(Symbol),                               // function of the calling contract
+    Base(Symbol),                                // function of the base contract
+    Delegate,                                    // delegatecall forwarding to an implementation
+    Revert(Option<Symbol>),                      // revert, the custom error if any
+    AddressCall(String),                         // low-level call of an address, e.g. `transfer`
+    Guard(String),                               // access check, the required role or account
+    OriginCheck,                    // check of `tx.origin`, which is not an access check
+    SendValue(String, Recipient),   // ETH sent by `transfer`, `send`, `call` or `selfdestruct`
+    Create(Option<String>, String), // created contract if known, how, e.g. `create2` or `clone`
+    Branch,                         // start of alternative paths, e.g. `if` and `else`
+    Path,                           // start of the next alternative path
+    Merge,                          // end of the alternative paths
+    Loop,                           // start of the calls a loop may repeat
+    EndLoop,                        // end of the calls a loop may repeat
+    Return,                         // end of the path of the current function body
+    Enter,                          // start of the calls of an inlined function body
+    Leave,                          // end of the calls of an inlined function body
+    Placeholder,                    // `_` of a modifier, where the modified function body runs
 }
 
 #[derive(Clone, Default, Debug)]
@@ -75,60 +342,111 @@ pub struct PoseidonOptions {
     pub omitted: Vec<String>,
     pub omit_read_storage: bool,
     pub group_floating_storage: bool,
+    pub deployment: bool,
+    pub link_implementations: bool,
+    pub report: bool,
+    pub actors: bool,
+    pub value_flow: bool,
 }
 
 impl Call {
     #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         match self {
-            Call::Read(call_type, contract, calling)
-            | Call::ReadStorage(call_type, contract, calling)
-            | Call::WriteStorage(call_type, contract, calling)
-            | Call::Write(call_type, contract, calling) => {
-                format!(
-                    "{}_{contract}_{calling}",
-                    if call_type == &CallType::CallingFunction {
-                        "f"
-                    } else {
-                        "s"
-                    },
-                )
+            Call::Read(call_type, symbol)
+            | Call::ReadStorage(call_type, symbol)
+            | Call::WriteStorage(call_type, symbol)
+            | Call::WriteStorageField(call_type, symbol, _)
+            | Call::Write(call_type, symbol) => {
+                symbol.mermaid_id(match call_type {
+                    CallType::CallingFunction => "f",
+                    CallType::CallingImmutable => "i",
+                    _ => "s",
+                })
             }
+            Call::External(symbol, _) => symbol.mermaid_id("f"),
+            Call::Emit(symbol) => symbol.mermaid_id("e"),
             _ => unreachable!("Must be remapped"),
         }
     }
 
-    pub fn change_contract(&self, new_contract: &str) -> Self {
-        match self.clone() {
-            Call::Read(call_type, _, calling) => {
-                Call::Read(call_type, new_contract.to_string(), calling)
-            }
-            Call::ReadStorage(call_type, _, calling) => {
-                Call::ReadStorage(call_type, new_contract.to_string(), calling)
+    pub fn change_contract(&self, new_contract: &Contract) -> Self {
+        let change = |symbol: &Symbol| {
+            Symbol::new(
+                &new_contract.source_unit,
+                &new_contract.name,
+                &symbol.member,
+            )
+        };
+
+        match self {
+            Call::Read(call_type, symbol) => Call::Read(call_type.clone(), change(symbol)),
+            Call::ReadStorage(call_type, symbol) => {
+                Call::ReadStorage(call_type.clone(), change(symbol))
             }
-            Call::Write(call_type, _, calling) => {
-                Call::Write(call_type, new_contract.to_string(), calling)
+            Call::Write(call_type, symbol) => Call::Write(call_type.clone(), change(symbol)),
+            Call::WriteStorage(call_type, symbol) => {
+                Call::WriteStorage(call_type.clone(), change(symbol))
             }
-            Call::WriteStorage(call_type, _, calling) => {
-                Call::WriteStorage(call_type, new_contract.to_string(), calling)
+            Call::WriteStorageField(call_type, symbol, field) => {
+                Call::WriteStorageField(call_type.clone(), change(symbol), field.clone())
             }
+            Call::Emit(symbol) => Call::Emit(change(symbol)),
+            // external calls always target the receiver type
+            Call::External(..) => self.clone(),
             _ => unreachable!("Must be remapped"),
         }
     }
 
+    /// Returns `true` if the call only marks the control flow of the function
+    pub fn is_flow_marker(&self) -> bool {
+        matches!(
+            self,
+            Call::Branch
+                | Call::Path
+                | Call::Merge
+                | Call::Loop
+                | Call::EndLoop
+                | Call::Return
+                | Call::Enter
+                | Call::Leave
+                | Call::Placeholder
+        )
+    }
+
+    /// Returns `true` if the call hands the control over to another contract or account,
+    /// calls of `view` and `pure` functions can not change the state
+    pub fn is_interaction(&self) -> bool {
+        matches!(
+            self,
+            Call::External(_, false) | Call::Delegate | Call::AddressCall(..)
+        )
+    }
+
     pub fn is_read_storage(&self) -> bool {
-        matches!(self, Call::ReadStorage(_, _, _))
+        matches!(self, Call::ReadStorage(_, _))
+    }
+
+    pub fn is_immutable_access(&self) -> bool {
+        matches!(
+            self,
+            Call::ReadStorage(CallType::CallingImmutable, _)
+                | Call::WriteStorage(CallType::CallingImmutable, _)
+                | Call::WriteStorageField(CallType::CallingImmutable, _, _)
+        )
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub enum CallType {
     CallingStorage,
+    CallingTransientStorage, // `tload` and `tstore` in assembly
     CallingStoragePointer,
+    CallingImmutable,
     CallingFunction,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Expression {
-    Modifier(String, Vec<Call>),
+    Modifier(String, Vec<Call>, Vec<String>), /* name, calls made by the arguments, arguments as written */
 }